@@ -1,7 +1,4 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::fs;
 
 use proc_macro::TokenStream;
 
@@ -12,6 +9,13 @@ use proc_macro::TokenStream;
 ///     - `Weight` type.
 ///     - `Graph` type.
 ///     - `NODE_COUNT` constant.
+///     - `LABELS` constant, with the original city labels from the csv header, so that node `i` of
+///       the graph is the city `LABELS[i]`.
+///
+/// The csv is validated while expanding the macro: the matrix must be square, each row must start
+/// with the same label as the corresponding header column and every entry must be a number. Only
+/// the diagonal may be left empty (it is read as `0.0`). Every problem found is reported as a
+/// `compile_error!` naming the row, the column and the offending text.
 ///
 /// # Examples
 ///
@@ -22,6 +26,7 @@ use proc_macro::TokenStream;
 ///
 /// // Will define
 /// const NODE_COUNT: usize = <some_number>;
+/// const LABELS: [&str; NODE_COUNT] = [<header labels>];
 /// type Node = usize;
 /// type Weight = f64;
 /// type Graph = [[Weight; NODE_COUNT] NODE_COUNT];
@@ -31,23 +36,155 @@ use proc_macro::TokenStream;
 pub fn graph_from_csv(item: TokenStream) -> TokenStream {
     let item_str = item.to_string();
     let path = item_str.trim_matches('"');
-    let file = BufReader::new(File::open(path).unwrap());
-    let mut data = Vec::new();
-
-    for line in file.lines().skip(1) {
-        data.push(Vec::new());
-        for record in line.unwrap().split(',').skip(1) {
-            if let Some(l) = data.last_mut() {
-                l.push(record.parse().unwrap_or(0.))
-            }
+    let (labels, data) = match fs::read_to_string(path)
+        .map_err(|e| vec![format!("cannot read `{path}`: {e}")])
+        .and_then(|content| parse(&content))
+    {
+        Ok(parsed) => parsed,
+        Err(errors) => {
+            return errors
+                .iter()
+                .map(|e| format!("compile_error!({:?});", format!("{path}: {e}")))
+                .collect::<String>()
+                .parse()
+                .unwrap();
         }
-    }
+    };
 
     let mut ret = String::new();
     ret.push_str(format!("const NODE_COUNT: usize = {};", data.len()).as_str());
+    ret.push_str(format!("const LABELS: [&str; NODE_COUNT] = {:?};", labels).as_str());
     ret.push_str("type Node = usize;");
     ret.push_str("type Weight = f64;");
     ret.push_str("type Graph = [[Weight; NODE_COUNT]; NODE_COUNT];");
     ret.push_str(format!("const g: Graph = {:?};", data).as_str());
     ret.parse().unwrap()
 }
+
+/// The header labels and the weights of an adjacency matrix.
+type Parsed = (Vec<String>, Vec<Vec<f64>>);
+
+/// Parses and validates the adjacency matrix, returning every problem found in the csv.
+fn parse(content: &str) -> Result<Parsed, Vec<String>> {
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let labels: Vec<String> = match lines.next() {
+        Some(header) => header
+            .split(',')
+            .skip(1)
+            .map(|l| l.trim().to_string())
+            .collect(),
+        None => return Err(vec!["empty csv, expected a header row".to_string()]),
+    };
+    let mut data = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, line) in lines.enumerate() {
+        let records: Vec<&str> = line.split(',').map(str::trim).collect();
+        let (row_label, records) = (records[0], &records[1..]);
+        let row_no = i + 1;
+        match labels.get(i) {
+            Some(label) if label == row_label => {}
+            Some(label) => errors.push(format!(
+                "row {row_no} is labelled `{row_label}`, but column {row_no} of the header is `{label}`"
+            )),
+            None => errors.push(format!(
+                "row {row_no} (`{row_label}`) has no matching label in the header"
+            )),
+        }
+
+        let mut row = Vec::new();
+        for (j, record) in records.iter().enumerate() {
+            let column = labels.get(j).map(String::as_str).unwrap_or("?");
+            let at = format!(
+                "row {row_no} (`{row_label}`), column {} (`{column}`)",
+                j + 1
+            );
+            match record.parse::<f64>() {
+                // `inf` and `NaN` parse, but are not valid tokens in the generated matrix.
+                Ok(w) if !w.is_finite() => errors.push(format!("{at}: `{record}` is not finite")),
+                Ok(w) => row.push(w),
+                Err(_) if record.is_empty() && i == j => row.push(0.),
+                Err(_) if record.is_empty() => errors.push(format!("{at}: missing weight")),
+                Err(_) => errors.push(format!("{at}: `{record}` is not a number")),
+            }
+        }
+        if records.len() != labels.len() {
+            errors.push(format!(
+                "row {row_no} (`{row_label}`) has {} entries, expected {}",
+                records.len(),
+                labels.len()
+            ));
+        }
+        data.push(row);
+    }
+
+    if data.len() != labels.len() {
+        let plural = |count: usize, noun: &str| match count {
+            1 => format!("1 {noun}"),
+            _ => format!("{count} {noun}s"),
+        };
+        errors.push(format!(
+            "matrix has {}, but the header has {}",
+            plural(data.len(), "row"),
+            plural(labels.len(), "label")
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok((labels, data))
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test_1() {
+        let (labels, data) = parse("Km,1,7\n1,,2.5\n7,2.5,\n").unwrap();
+
+        assert_eq!(labels, ["1", "7"]);
+        assert_eq!(data, [[0.0, 2.5], [2.5, 0.0]]);
+    }
+
+    #[test]
+    fn parse_test_2() {
+        let errors = parse("Km,1,7,8\n1,,2,x\n7,2,,\n9,3,4,\n").unwrap_err();
+
+        assert_eq!(
+            errors,
+            [
+                "row 1 (`1`), column 3 (`8`): `x` is not a number",
+                "row 2 (`7`), column 3 (`8`): missing weight",
+                "row 3 is labelled `9`, but column 3 of the header is `8`",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_test_3() {
+        let errors = parse("Km,1,7\n1,,2\n").unwrap_err();
+
+        assert_eq!(errors, ["matrix has 1 row, but the header has 2 labels"]);
+        let errors = parse("Km,1\n1,\n7,2\n").unwrap_err();
+        assert_eq!(
+            errors.last().unwrap(),
+            "matrix has 2 rows, but the header has 1 label"
+        );
+    }
+
+    #[test]
+    fn parse_test_4() {
+        let errors = parse("Km,1,7\n1,,inf\n7,NaN,\n").unwrap_err();
+
+        assert_eq!(
+            errors,
+            [
+                "row 1 (`1`), column 2 (`7`): `inf` is not finite",
+                "row 2 (`7`), column 1 (`1`): `NaN` is not finite",
+            ]
+        );
+    }
+}
@@ -12,4 +12,6 @@ print("Avg cost:", df[0].mean())
 print("Standard deviation (cost):", df[0].std())
 print(f"Avg time: {df[1].mean()}s")
 print("Standard deviation (time):", df[1].std())
+if len(df.columns) > 2:
+    print(f"Avg gap: {100 * df[2].mean()}%")
 print("Count:", len(df))
@@ -5,7 +5,10 @@ END_TIME=$(($(date +%s) + $DURATION))
 TEMP_DIR=/tmp/grasp
 RESULT=${1:-result.txt}
 if [ -n "$1" ]; then shift 1; fi
-PARAMS=${@:-"100 100 0.01"}
+PARAMS=${@:-"data/012/data.csv 100 100 0.01"}
+BIN=${BIN:-memetic}
+# The lower bound only depends on the instance (the first parameter), so compute it once.
+export BOUND=${BOUND:-$(./target/release/bound ${PARAMS%% *})}
 
 rm -rf $TEMP_DIR
 mkdir -p $TEMP_DIR
@@ -13,8 +16,7 @@ mkdir -p $TEMP_DIR
 for i in $(seq $(nproc)); do
     TEMP_RESULT=$TEMP_DIR/result_$i.txt
     while [ $(date +%s) -lt $END_TIME ]; do
-        # TODO: change this to the desired bin depending on the algorithm
-        ./target/release/memetic $PARAMS >> $TEMP_RESULT
+        ./target/release/$BIN $PARAMS >> $TEMP_RESULT
     done &
 done
 
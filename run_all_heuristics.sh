@@ -1,9 +1,10 @@
 #!/bin/sh
 
+cargo br
+
 for f in data/*; do
     fname=$(basename $f)
     num=$(echo $fname | grep -o -E '[1-9]+[0-9]*')
-    sed -i "s|\(graph_from_csv!(\"\)[^\"]*\(\")\)|\1$f/data.csv\2|" ./src/bin/heuristics.rs
     echo -n "instance $num: \n"
-    cargo rr --bin heuristics 2> /dev/null
+    ./target/release/heuristics $f/data.csv
 done
@@ -0,0 +1,440 @@
+//! Otimização por colônia de formigas (ACO, *Ant Colony Optimization*).
+//!
+//! A cada iteração, cada formiga constrói uma rota a partir de um vértice sorteado, escolhendo o
+//! próximo vértice `v` a partir de `u` com probabilidade proporcional a `τ(u, v)^α η(u, v)^β`,
+//! onde `τ` é o feromônio da aresta e `η = 1 / d(u, v)` é a informação heurística. Só os vizinhos
+//! de `u` na [`CandidateList`] são sorteados; quando todos já foram visitados, a formiga vai para
+//! o vértice não visitado de maior `τ^α η^β`. Depois, o feromônio evapora a uma taxa `ρ` e é
+//! reforçado nas arestas das melhores rotas. As variantes diferem em quem deposita feromônio:
+//! - no *Ant System*, todas as formigas, proporcionalmente à qualidade das suas rotas;
+//! - no *MAX-MIN Ant System*, só a melhor formiga da iteração, e o feromônio fica limitado a um
+//!   intervalo `[τ_min, τ_max]`, o que evita a estagnação;
+//! - no *Ant Colony System*, só a melhor rota encontrada. A escolha do próximo vértice é gulosa
+//!   com probabilidade `q0`, e cada passo de uma formiga retira feromônio da aresta usada,
+//!   incentivando as formigas seguintes a explorar outras arestas.
+//!
+//! Opcionalmente, a rota de cada formiga é refinada por uma busca local de
+//! [`LocalSearch`](crate::local_search::LocalSearch) antes da atualização do feromônio. O
+//! feromônio é simétrico.
+
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::candidates::CandidateList;
+use crate::construction;
+use crate::graphs::{DistanceMatrix, Matrix};
+use crate::local_search::{DynSolution, Neighbourhood, Solution};
+
+/// Menor distância considerada no cálculo de `η`, para que arestas de custo 0 não dividam por 0.
+const EPSILON: f64 = 1e-9;
+
+/// Fração do feromônio de uma aresta retirada a cada passo de uma formiga no ACS.
+const LOCAL_EVAPORATION: f64 = 0.1;
+
+/// Probabilidade, no MMAS, de a colônia convergida construir a melhor rota; define `τ_min`.
+const P_BEST: f64 = 0.05;
+
+/// Variante da colônia.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Variant {
+    AntSystem,
+    #[default]
+    MaxMin,
+    AntColonySystem,
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "as" => Ok(Self::AntSystem),
+            "mmas" => Ok(Self::MaxMin),
+            "acs" => Ok(Self::AntColonySystem),
+            _ => Err(format!(
+                "unknown ant colony variant `{s}`, expected `as`, `mmas` or `acs`"
+            )),
+        }
+    }
+}
+
+/// Configuração da colônia.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aco {
+    pub variant: Variant,
+    /// Quantidade de formigas por iteração.
+    pub ants: usize,
+    /// Peso do feromônio.
+    pub alpha: f64,
+    /// Peso da informação heurística.
+    pub beta: f64,
+    /// Taxa de evaporação do feromônio.
+    pub rho: f64,
+    /// Quantidade de vizinhos mais próximos sorteados a cada passo de uma formiga.
+    pub candidates: usize,
+    /// Probabilidade de uma formiga do ACS escolher o melhor vértice em vez de sorteá-lo.
+    pub q0: f64,
+    /// Busca local aplicada à rota de cada formiga; `None` não aplica busca local.
+    pub local_search: Option<Neighbourhood>,
+    /// Quantidade máxima de iterações.
+    pub max_iterations: Option<usize>,
+    /// Tempo máximo de execução.
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for Aco {
+    fn default() -> Self {
+        Self {
+            variant: Variant::default(),
+            ants: 20,
+            alpha: 1.,
+            beta: 3.,
+            rho: 0.1,
+            candidates: 15,
+            q0: 0.9,
+            local_search: None,
+            max_iterations: Some(200),
+            time_limit: None,
+        }
+    }
+}
+
+/// Resultado da colônia.
+#[derive(Clone)]
+pub struct AcoResult {
+    /// Melhor rota encontrada.
+    pub solution: DynSolution,
+    /// Quantidade de iterações executadas.
+    pub iterations: usize,
+    /// Iteração em que a melhor rota foi encontrada.
+    pub best_iteration: usize,
+}
+
+/// Feromônio e pesos `τ^α η^β` de todas as arestas.
+struct Colony<'a> {
+    params: &'a Aco,
+    candidates: CandidateList,
+    pheromone: Matrix,
+    /// `η^β` de cada aresta.
+    heuristic: Matrix,
+    weight: Matrix,
+    /// Feromônio inicial.
+    initial: f64,
+    /// Limites do feromônio no MMAS.
+    bounds: (f64, f64),
+}
+
+impl Colony<'_> {
+    /// Recalcula o peso da aresta `(u, v)` nos dois sentidos.
+    fn reweigh(&mut self, u: usize, v: usize) {
+        let w = self.pheromone[u][v].powf(self.params.alpha) * self.heuristic[u][v];
+        self.weight[u][v] = w;
+        self.weight[v][u] = self.pheromone[v][u].powf(self.params.alpha) * self.heuristic[v][u];
+    }
+
+    /// Define o feromônio da aresta `(u, v)`, nos dois sentidos.
+    fn set(&mut self, u: usize, v: usize, tau: f64) {
+        self.pheromone[u][v] = tau;
+        self.pheromone[v][u] = tau;
+    }
+
+    /// Soma `amount` ao feromônio da aresta `(u, v)`, nos dois sentidos.
+    fn deposit(&mut self, u: usize, v: usize, amount: f64) {
+        self.set(u, v, self.pheromone[u][v] + amount);
+    }
+
+    /// Limites `[τ_min, τ_max]` do MMAS para a melhor rota de custo `best`.
+    fn max_min_bounds(&self, best: f64) -> (f64, f64) {
+        let n = self.pheromone.len() as f64;
+        let max = 1. / (self.params.rho * best);
+        let root = P_BEST.powf(1. / n);
+        let min = max * (1. - root) / ((n / 2. - 1.) * root);
+        (min.min(max), max)
+    }
+
+    /// Constrói a rota de uma formiga a partir de `start`.
+    fn construct<R: Rng + ?Sized>(&mut self, start: usize, rng: &mut R) -> Vec<usize> {
+        let n = self.pheromone.len();
+        let mut visited = vec![false; n];
+        visited[start] = true;
+        let mut route = Vec::with_capacity(n);
+        route.push(start);
+
+        let mut u = start;
+        while route.len() < n {
+            let options: Vec<usize> = self
+                .candidates
+                .neighbours(u)
+                .iter()
+                .copied()
+                .filter(|&v| !visited[v])
+                .collect();
+            let greedy = options.is_empty()
+                || (self.params.variant == Variant::AntColonySystem
+                    && rng.random_bool(self.params.q0));
+
+            let v = if greedy {
+                let pool = if options.is_empty() {
+                    (0..n).filter(|&v| !visited[v]).collect()
+                } else {
+                    options
+                };
+                pool.into_iter()
+                    .max_by(|&a, &b| self.weight[u][a].total_cmp(&self.weight[u][b]))
+                    .unwrap()
+            } else {
+                self.roulette(u, &options, rng)
+            };
+
+            if self.params.variant == Variant::AntColonySystem {
+                let tau = (1. - LOCAL_EVAPORATION) * self.pheromone[u][v]
+                    + LOCAL_EVAPORATION * self.initial;
+                self.set(u, v, tau);
+                self.reweigh(u, v);
+            }
+            visited[v] = true;
+            route.push(v);
+            u = v;
+        }
+        route
+    }
+
+    /// Sorteia um vértice de `options` com probabilidade proporcional ao peso da aresta a partir
+    /// de `u`.
+    fn roulette<R: Rng + ?Sized>(&self, u: usize, options: &[usize], rng: &mut R) -> usize {
+        let total: f64 = options.iter().map(|&v| self.weight[u][v]).sum();
+        if !(total > 0. && total.is_finite()) {
+            return options[rng.random_range(0..options.len())];
+        }
+        let mut r = rng.random_range(0.0..total);
+        for &v in options {
+            r -= self.weight[u][v];
+            if r < 0. {
+                return v;
+            }
+        }
+        *options.last().unwrap()
+    }
+
+    /// Evapora o feromônio e o reforça com as rotas `tours` e a melhor rota `best`.
+    fn update(&mut self, tours: &[DynSolution], best: &DynSolution) {
+        let n = self.pheromone.len();
+        let rho = self.params.rho;
+        match self.params.variant {
+            Variant::AntSystem => {
+                self.evaporate(rho);
+                for tour in tours {
+                    for (u, v) in edges(&tour.route) {
+                        self.deposit(u, v, 1. / tour.cost);
+                    }
+                }
+            }
+            Variant::MaxMin => {
+                self.evaporate(rho);
+                let iteration_best = tours
+                    .iter()
+                    .min_by(|a, b| a.cost.total_cmp(&b.cost))
+                    .unwrap();
+                for (u, v) in edges(&iteration_best.route) {
+                    self.deposit(u, v, 1. / iteration_best.cost);
+                }
+                self.bounds = self.max_min_bounds(best.cost);
+                let (min, max) = self.bounds;
+                for u in 0..n {
+                    for tau in &mut self.pheromone[u] {
+                        *tau = tau.clamp(min, max);
+                    }
+                }
+            }
+            // Só as arestas da melhor rota evaporam e recebem feromônio.
+            Variant::AntColonySystem => {
+                for (u, v) in edges(&best.route) {
+                    let tau = (1. - rho) * self.pheromone[u][v] + rho / best.cost;
+                    self.set(u, v, tau);
+                }
+            }
+        }
+
+        for u in 0..n {
+            for v in 0..n {
+                self.weight[u][v] =
+                    self.pheromone[u][v].powf(self.params.alpha) * self.heuristic[u][v];
+            }
+        }
+    }
+
+    /// Multiplica o feromônio de todas as arestas por `1 - rho`.
+    fn evaporate(&mut self, rho: f64) {
+        for u in 0..self.pheromone.len() {
+            for tau in &mut self.pheromone[u] {
+                *tau *= 1. - rho;
+            }
+        }
+    }
+}
+
+/// Arestas da rota `route`, incluindo a de retorno ao início.
+fn edges(route: &[usize]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..route.len()).map(|i| (route[i], route[(i + 1) % route.len()]))
+}
+
+impl Aco {
+    /// Executa a colônia sobre `graph`.
+    pub fn run<G: DistanceMatrix, R: Rng + ?Sized>(&self, graph: &G, rng: &mut R) -> AcoResult {
+        assert!(
+            self.max_iterations.is_some() || self.time_limit.is_some(),
+            "ant colony needs an iteration or time budget"
+        );
+        let now = Instant::now();
+        let n = graph.node_count();
+
+        // A rota do Vizinho Mais Próximo define a escala do feromônio inicial.
+        let mut best = construction::nearest_neighbour(graph, 0);
+        let mut result = AcoResult {
+            solution: best.clone(),
+            iterations: 0,
+            best_iteration: 0,
+        };
+        // Com menos de 4 vértices, só há uma rota a menos do sentido: a do Vizinho Mais Próximo.
+        if n < 4 {
+            return result;
+        }
+
+        let initial = match self.variant {
+            Variant::AntSystem => self.ants as f64 / best.cost,
+            Variant::MaxMin => 1. / (self.rho * best.cost),
+            Variant::AntColonySystem => 1. / (n as f64 * best.cost),
+        };
+        let heuristic = Matrix::from_fn(n, |u, v| {
+            if u == v {
+                0.
+            } else {
+                graph.dist(u, v).max(EPSILON).recip().powf(self.beta)
+            }
+        });
+        let mut colony = Colony {
+            params: self,
+            candidates: CandidateList::new(graph, self.candidates),
+            pheromone: Matrix::filled(n, initial),
+            weight: Matrix::from_fn(n, |u, v| initial.powf(self.alpha) * heuristic[u][v]),
+            heuristic,
+            initial,
+            bounds: (0., initial),
+        };
+        colony.bounds = colony.max_min_bounds(best.cost);
+
+        let mut iterations = 0;
+        while self.max_iterations.is_none_or(|max| iterations < max)
+            && self.time_limit.is_none_or(|limit| now.elapsed() < limit)
+        {
+            iterations += 1;
+            let tours: Vec<DynSolution> = (0..self.ants.max(1))
+                .map(|_| {
+                    let route = colony.construct(rng.random_range(0..n), rng);
+                    let cost = DynSolution::calculate_cost(&route, graph);
+                    let tour = Solution { route, cost };
+                    match self.local_search {
+                        Some(neighbourhood) => {
+                            neighbourhood.search(&tour, graph, rng.random_range(0..n))
+                        }
+                        None => tour,
+                    }
+                })
+                .collect();
+
+            let iteration_best = tours
+                .iter()
+                .min_by(|a, b| a.cost.total_cmp(&b.cost))
+                .unwrap();
+            if iteration_best.cost < best.cost - EPSILON {
+                best = iteration_best.clone();
+                result.best_iteration = iterations;
+            }
+            colony.update(&tours, &best);
+        }
+
+        AcoResult {
+            solution: best,
+            iterations,
+            ..result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact;
+    use crate::test_util::{euclidean, is_permutation};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn aco_test_1() {
+        let graph = euclidean(12, 1);
+        let optimum = exact::held_karp(&graph).unwrap().cost;
+        let nearest = construction::nearest_neighbour(&graph, 0).cost;
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for variant in ["as", "mmas", "acs"] {
+            let aco = Aco {
+                variant: variant.parse().unwrap(),
+                candidates: 5,
+                max_iterations: Some(100),
+                ..Aco::default()
+            };
+            let result = aco.run(&graph, &mut rng);
+
+            assert_eq!(result.iterations, 100);
+            assert!(is_permutation(&result.solution.route));
+            assert_eq!(
+                result.solution.cost,
+                DynSolution::calculate_cost(&result.solution.route, &graph)
+            );
+            assert!(result.solution.cost >= optimum - 1e-9);
+            assert!(result.solution.cost <= nearest);
+            assert!(result.solution.cost <= 1.05 * optimum, "{variant}");
+        }
+    }
+
+    #[test]
+    fn aco_test_2() {
+        let graph = euclidean(30, 2);
+        let mut rng = StdRng::seed_from_u64(2);
+
+        let aco = Aco {
+            ants: 10,
+            local_search: Some(Neighbourhood::TwoOpt),
+            max_iterations: Some(20),
+            ..Aco::default()
+        };
+        let result = aco.run(&graph, &mut rng);
+        assert!(is_permutation(&result.solution.route));
+        assert!(result.best_iteration > 0);
+
+        // No MMAS, o feromônio fica dentro dos limites após cada atualização.
+        let mut colony = Colony {
+            params: &aco,
+            candidates: CandidateList::new(&graph, 5),
+            pheromone: Matrix::filled(30, 1.),
+            heuristic: Matrix::filled(30, 1.),
+            weight: Matrix::filled(30, 1.),
+            initial: 1.,
+            bounds: (0., 1.),
+        };
+        let tour = construction::nearest_neighbour(&graph, 0);
+        colony.update(std::slice::from_ref(&tour), &tour);
+        let (min, max) = colony.bounds;
+        assert!(0. < min && min < max);
+        assert!(
+            colony
+                .pheromone
+                .rows()
+                .flatten()
+                .all(|&t| min <= t && t <= max)
+        );
+        let (u, v) = (tour.route[0], tour.route[1]);
+        assert_eq!(colony.pheromone[u][v], colony.pheromone[v][u]);
+    }
+}
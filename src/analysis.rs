@@ -0,0 +1,310 @@
+//! Validação e análise métrica de instâncias.
+//!
+//! Várias heurísticas (como a Inserção Mais Próxima) e buscas locais assumem, implicitamente, que
+//! a matriz de distâncias é uma métrica: simétrica, não negativa, finita e obedecendo à
+//! desigualdade triangular. A função [`analyse`] verifica essas propriedades e gera um
+//! [`Report`] com as violações encontradas e estatísticas sobre os custos das arestas.
+
+use std::fmt;
+
+use crate::graphs::DistanceMatrix;
+
+/// Tolerância relativa usada ao comparar custos, para não acusar violações causadas apenas por
+/// arredondamento (e.g. `26.4 + 16.1` contra `42.5`).
+const EPSILON: f64 = 1e-9;
+
+/// Par de vértices cujos custos de ida e volta são diferentes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SymmetryViolation {
+    pub u: usize,
+    pub v: usize,
+    /// Custo de `u` para `v`.
+    pub uv: f64,
+    /// Custo de `v` para `u`.
+    pub vu: f64,
+}
+
+/// Trio de vértices onde o caminho `u → w → v` é mais barato que a aresta direta `u → v`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriangleViolation {
+    pub u: usize,
+    pub v: usize,
+    pub w: usize,
+    /// Quanto a aresta direta excede o desvio: `d(u, v) - (d(u, w) + d(w, v))`.
+    pub excess: f64,
+}
+
+/// Estatísticas (mínimo, máximo e média) de um conjunto de custos.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+/// Relatório com as propriedades métricas de uma instância.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// Quantidade de vértices.
+    pub node_count: usize,
+    /// Pares `(u, v)`, com `u < v`, onde `d(u, v) != d(v, u)`.
+    pub symmetry_violations: Vec<SymmetryViolation>,
+    /// Quantidade de trios que violam a desigualdade triangular.
+    pub triangle_violations: usize,
+    /// A violação da desigualdade triangular com maior excesso, se houver alguma.
+    pub worst_triangle_violation: Option<TriangleViolation>,
+    /// Quantidade de arestas com custo negativo.
+    pub negative_weights: usize,
+    /// Quantidade de arestas com custo infinito ou `NaN`.
+    pub non_finite_weights: usize,
+    /// Estatísticas dos custos finitos das arestas (fora da diagonal).
+    pub weights: Option<Stats>,
+    /// Estatísticas do custo de cada vértice até o seu vizinho mais próximo.
+    pub nearest_neighbour: Option<Stats>,
+}
+
+impl Report {
+    pub fn is_symmetric(&self) -> bool {
+        self.symmetry_violations.is_empty()
+    }
+
+    /// Verifica se a instância é uma métrica: simétrica, com custos finitos e não negativos e
+    /// obedecendo à desigualdade triangular.
+    pub fn is_metric(&self) -> bool {
+        self.is_symmetric()
+            && self.triangle_violations == 0
+            && self.negative_weights == 0
+            && self.non_finite_weights == 0
+    }
+}
+
+/// Analisa a matriz de distâncias `graph`, considerando apenas as arestas fora da diagonal.
+///
+/// A verificação da desigualdade triangular testa todos os trios de vértices, custando O(n³).
+pub fn analyse<G: DistanceMatrix + ?Sized>(graph: &G) -> Report {
+    let n = graph.node_count();
+    let mut symmetry_violations = Vec::new();
+    let mut negative_weights = 0;
+    let mut non_finite_weights = 0;
+    let mut weights = StatsBuilder::default();
+    let mut nearest = StatsBuilder::default();
+
+    for u in 0..n {
+        let mut nearest_u = f64::INFINITY;
+        for v in (0..n).filter(|&v| v != u) {
+            let uv = graph.dist(u, v);
+            // A simetria é verificada antes de descartar os custos infinitos.
+            let vu = graph.dist(v, u);
+            if u < v && !approx_eq(uv, vu) {
+                symmetry_violations.push(SymmetryViolation { u, v, uv, vu });
+            }
+
+            if !uv.is_finite() {
+                non_finite_weights += 1;
+                continue;
+            }
+            if uv < 0. {
+                negative_weights += 1;
+            }
+            weights.push(uv);
+            nearest_u = nearest_u.min(uv);
+        }
+        if nearest_u.is_finite() {
+            nearest.push(nearest_u);
+        }
+    }
+
+    let mut triangle_violations = 0;
+    let mut worst_triangle_violation: Option<TriangleViolation> = None;
+    for u in 0..n {
+        for v in (0..n).filter(|&v| v != u) {
+            let uv = graph.dist(u, v);
+            for w in (0..n).filter(|&w| w != u && w != v) {
+                let detour = graph.dist(u, w) + graph.dist(w, v);
+                if uv > detour && !approx_eq(uv, detour) {
+                    triangle_violations += 1;
+                    let excess = uv - detour;
+                    if worst_triangle_violation.is_none_or(|t| excess > t.excess) {
+                        worst_triangle_violation = Some(TriangleViolation { u, v, w, excess });
+                    }
+                }
+            }
+        }
+    }
+
+    Report {
+        node_count: n,
+        symmetry_violations,
+        triangle_violations,
+        worst_triangle_violation,
+        negative_weights,
+        non_finite_weights,
+        weights: weights.build(),
+        nearest_neighbour: nearest.build(),
+    }
+}
+
+/// Compara com tolerância relativa; um infinito só é igual a outro de mesmo sinal.
+fn approx_eq(a: f64, b: f64) -> bool {
+    a == b
+        || (a.is_finite()
+            && b.is_finite()
+            && (a - b).abs() <= EPSILON * a.abs().max(b.abs()).max(1.))
+}
+
+#[derive(Default)]
+struct StatsBuilder {
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: usize,
+}
+
+impl StatsBuilder {
+    fn push(&mut self, x: f64) {
+        if self.count == 0 {
+            (self.min, self.max) = (x, x);
+        }
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.sum += x;
+        self.count += 1;
+    }
+
+    fn build(self) -> Option<Stats> {
+        (self.count > 0).then(|| Stats {
+            min: self.min,
+            max: self.max,
+            mean: self.sum / self.count as f64,
+        })
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {} / max {} / mean {:.3}",
+            self.min, self.max, self.mean
+        )
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        writeln!(f, "Nodes: {}", self.node_count)?;
+        writeln!(f, "Metric: {}", yes_no(self.is_metric()))?;
+        writeln!(
+            f,
+            "Symmetric: {} ({} violations)",
+            yes_no(self.is_symmetric()),
+            self.symmetry_violations.len()
+        )?;
+        for s in self.symmetry_violations.iter().take(5) {
+            writeln!(
+                f,
+                "  d({}, {}) = {} but d({}, {}) = {}",
+                s.u, s.v, s.uv, s.v, s.u, s.vu
+            )?;
+        }
+        writeln!(
+            f,
+            "Triangle inequality violations: {}",
+            self.triangle_violations
+        )?;
+        if let Some(t) = self.worst_triangle_violation {
+            writeln!(
+                f,
+                "  worst: d({}, {}) exceeds the detour through {} by {}",
+                t.u, t.v, t.w, t.excess
+            )?;
+        }
+        writeln!(f, "Negative weights: {}", self.negative_weights)?;
+        writeln!(f, "Infinite or NaN weights: {}", self.non_finite_weights)?;
+        if let Some(w) = self.weights {
+            writeln!(f, "Edge weights: {w}")?;
+        }
+        if let Some(nn) = self.nearest_neighbour {
+            writeln!(f, "Nearest neighbour distance: {nn}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const INF: f64 = f64::INFINITY;
+
+    #[test]
+    fn metric_test() {
+        let graph = vec![
+            vec![0.0, 3.0, 4.0, 5.0],
+            vec![3.0, 0.0, 5.0, 4.0],
+            vec![4.0, 5.0, 0.0, 3.0],
+            vec![5.0, 4.0, 3.0, 0.0],
+        ];
+
+        let report = analyse(&graph);
+
+        assert!(report.is_metric());
+        assert_eq!(
+            report.weights,
+            Some(Stats {
+                min: 3.0,
+                max: 5.0,
+                mean: 4.0
+            })
+        );
+        assert_eq!(report.nearest_neighbour.unwrap().max, 3.0);
+    }
+
+    #[test]
+    fn violations_test() {
+        let graph = vec![
+            vec![0.0, 1.0, 10.0],
+            vec![2.0, 0.0, 1.0],
+            vec![10.0, 1.0, INF],
+        ];
+
+        let report = analyse(&graph);
+
+        assert!(!report.is_metric());
+        assert_eq!(
+            report.symmetry_violations,
+            [SymmetryViolation {
+                u: 0,
+                v: 1,
+                uv: 1.0,
+                vu: 2.0
+            }]
+        );
+        assert_eq!(report.triangle_violations, 2);
+        assert_eq!(report.worst_triangle_violation.unwrap().excess, 8.0);
+        assert_eq!(report.non_finite_weights, 0);
+    }
+
+    #[test]
+    fn non_finite_test() {
+        let graph = vec![
+            vec![0.0, INF, 1.0],
+            vec![1.0, 0.0, INF],
+            vec![1.0, INF, 0.0],
+        ];
+
+        let report = analyse(&graph);
+
+        assert!(!report.is_symmetric());
+        assert_eq!(
+            report.symmetry_violations,
+            [SymmetryViolation {
+                u: 0,
+                v: 1,
+                uv: INF,
+                vu: 1.0
+            }]
+        );
+        assert_eq!(report.non_finite_weights, 3);
+    }
+}
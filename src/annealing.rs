@@ -0,0 +1,444 @@
+//! Têmpera simulada (*simulated annealing*).
+//!
+//! A cada iteração, um movimento aleatório de uma das vizinhanças de [`local_search`] (swap,
+//! shift, 2-opt ou or-opt) é sorteado e avaliado em O(1) por [`Move::delta`]. Movimentos que não
+//! pioram a rota são sempre aceitos; os que pioram em `Δ` são aceitos com probabilidade
+//! `exp(-Δ / T)`. A temperatura `T` é atualizada ao fim de cada época (um número fixo de
+//! iterações) segundo o [`Cooling`] escolhido, e a taxa de aceitação de cada época é registrada
+//! para acompanhar a convergência.
+//!
+//! [`local_search`]: crate::local_search
+
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use rand::seq::IndexedRandom;
+
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{Move, Solution};
+
+/// Melhora mínima para que uma rota substitua a melhor conhecida.
+const EPSILON: f64 = 1e-9;
+
+/// Vizinhança de onde os movimentos aleatórios são sorteados.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    Swap,
+    Shift,
+    TwoOpt,
+    OrOpt,
+}
+
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "swap" => Ok(Self::Swap),
+            "shift" => Ok(Self::Shift),
+            "2opt" => Ok(Self::TwoOpt),
+            "oropt" => Ok(Self::OrOpt),
+            _ => Err(format!(
+                "unsupported annealing move `{s}`, expected `swap`, `shift`, `2opt` or `oropt`"
+            )),
+        }
+    }
+}
+
+/// Esquema de resfriamento, aplicado ao fim de cada época.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cooling {
+    /// `T ← alpha T`.
+    Geometric { alpha: f64 },
+    /// `T` decresce linearmente da temperatura inicial até 0 ao longo do orçamento de iterações
+    /// ou de tempo.
+    Linear,
+    /// Lundy–Mees: `T ← T / (1 + beta T)`.
+    LundyMees { beta: f64 },
+    /// Geométrico com reaquecimento adaptativo: quando a taxa de aceitação de uma época fica
+    /// abaixo de `min_acceptance`, a busca está congelada e `T` volta para `reheat` vezes a
+    /// temperatura inicial.
+    Reheating {
+        alpha: f64,
+        min_acceptance: f64,
+        reheat: f64,
+    },
+}
+
+impl FromStr for Cooling {
+    type Err = String;
+
+    /// Esquemas com os parâmetros padrão: `geometric`, `linear`, `lundy-mees` e `reheating`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "geometric" => Ok(Self::Geometric { alpha: 0.95 }),
+            "linear" => Ok(Self::Linear),
+            "lundy-mees" => Ok(Self::LundyMees { beta: 1e-3 }),
+            "reheating" => Ok(Self::Reheating {
+                alpha: 0.95,
+                min_acceptance: 0.01,
+                reheat: 0.5,
+            }),
+            _ => Err(format!(
+                "unknown cooling schedule `{s}`, expected `geometric`, `linear`, `lundy-mees` or \
+                 `reheating`"
+            )),
+        }
+    }
+}
+
+/// Temperatura inicial.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InitialTemperature {
+    Fixed(f64),
+    /// Estimada por [`estimate_temperature`] para que um movimento de piora médio seja aceito
+    /// com probabilidade `acceptance`, a partir de `samples` movimentos sorteados.
+    Estimated {
+        acceptance: f64,
+        samples: usize,
+    },
+}
+
+/// Configuração da têmpera simulada. O resfriamento linear se baseia no orçamento de iterações ou
+/// de tempo, então ao menos um deles é obrigatório.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annealing {
+    /// Vizinhanças de onde os movimentos são sorteados, com a mesma probabilidade; não pode ser
+    /// vazia. O 2-opt assume custos simétricos.
+    pub moves: Vec<Neighbourhood>,
+    pub cooling: Cooling,
+    pub initial_temperature: InitialTemperature,
+    /// Quantidade de iterações com a mesma temperatura.
+    pub epoch: usize,
+    /// Quantidade máxima de iterações (movimentos sorteados).
+    pub max_iterations: Option<usize>,
+    /// Tempo máximo de execução.
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for Annealing {
+    fn default() -> Self {
+        Self {
+            moves: vec![
+                Neighbourhood::Swap,
+                Neighbourhood::Shift,
+                Neighbourhood::TwoOpt,
+                Neighbourhood::OrOpt,
+            ],
+            cooling: Cooling::Geometric { alpha: 0.95 },
+            initial_temperature: InitialTemperature::Estimated {
+                acceptance: 0.8,
+                samples: 100,
+            },
+            epoch: 100,
+            max_iterations: Some(100_000),
+            time_limit: None,
+        }
+    }
+}
+
+/// Estatísticas de uma época.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Epoch {
+    /// Temperatura durante a época.
+    pub temperature: f64,
+    /// Fração dos movimentos sorteados que foram aceitos.
+    pub acceptance_rate: f64,
+    /// Custo da melhor rota ao fim da época.
+    pub best_cost: f64,
+}
+
+/// Resultado da têmpera simulada.
+#[derive(Clone)]
+pub struct AnnealingResult<S> {
+    /// Melhor rota encontrada.
+    pub solution: S,
+    pub initial_temperature: f64,
+    /// Quantidade de iterações executadas.
+    pub iterations: usize,
+    /// Estatísticas de cada época, em ordem.
+    pub trace: Vec<Epoch>,
+}
+
+/// Sorteia um movimento de `neighbourhood` em uma rota com `n >= 4` vértices.
+fn random_move<R: Rng + ?Sized>(neighbourhood: Neighbourhood, n: usize, rng: &mut R) -> Move {
+    let mut distinct = || {
+        let i = rng.random_range(0..n);
+        let j = (i + rng.random_range(1..n)) % n;
+        (i, j)
+    };
+    match neighbourhood {
+        Neighbourhood::Swap => {
+            let (i, j) = distinct();
+            Move::Swap(i, j)
+        }
+        Neighbourhood::Shift => {
+            let (from, to) = distinct();
+            Move::Shift { from, to }
+        }
+        // As arestas `(i, i + 1)` e `(j, j + 1)` não podem ser adjacentes.
+        Neighbourhood::TwoOpt => loop {
+            let (i, j) = distinct();
+            let (i, j) = (i.min(j), i.max(j));
+            if j >= i + 2 && !(i == 0 && j == n - 1) {
+                break Move::TwoOpt(i, j);
+            }
+        },
+        Neighbourhood::OrOpt => {
+            let len = rng.random_range(1..=3.min(n - 2));
+            let start = rng.random_range(0..=n - len);
+            let to = (start + rng.random_range(1..n - len)) % (n - len);
+            Move::OrOpt { start, len, to }
+        }
+    }
+}
+
+/// Temperatura inicial que aceita com probabilidade `acceptance` a piora média entre `samples`
+/// movimentos sorteados a partir de `solution`: `T0 = -Δ / ln(acceptance)`. Se nenhum movimento
+/// piorar a rota, retorna 1.
+pub fn estimate_temperature<const N: usize, G: DistanceMatrix, R: Rng + ?Sized>(
+    solution: &Solution<N>,
+    graph: &G,
+    moves: &[Neighbourhood],
+    acceptance: f64,
+    samples: usize,
+    rng: &mut R,
+) -> f64 {
+    let n = solution.route.len();
+    let worsening: Vec<f64> = (0..samples)
+        .map(|_| random_move(*moves.choose(rng).unwrap(), n, rng).delta(&solution.route, graph))
+        .filter(|&delta| delta > EPSILON)
+        .collect();
+    if worsening.is_empty() {
+        return 1.;
+    }
+    let mean = worsening.iter().sum::<f64>() / worsening.len() as f64;
+    -mean / acceptance.ln()
+}
+
+impl Annealing {
+    /// Executa a têmpera simulada a partir de `solution`.
+    pub fn run<const N: usize, G: DistanceMatrix, R: Rng + ?Sized>(
+        &self,
+        solution: &Solution<N>,
+        graph: &G,
+        rng: &mut R,
+    ) -> AnnealingResult<Solution<N>> {
+        assert!(
+            self.max_iterations.is_some() || self.time_limit.is_some(),
+            "simulated annealing needs an iteration or time budget"
+        );
+        assert!(
+            !self.moves.is_empty(),
+            "expected at least one neighbourhood"
+        );
+        let now = Instant::now();
+        let n = solution.route.len();
+        let mut current = Solution {
+            route: solution.route.clone(),
+            cost: Solution::<N>::calculate_cost(&solution.route, graph),
+        };
+
+        // Com menos de 4 vértices, não há 2-opt válido para `random_move` sortear.
+        if n < 4 {
+            return AnnealingResult {
+                solution: current,
+                initial_temperature: 0.,
+                iterations: 0,
+                trace: Vec::new(),
+            };
+        }
+
+        let initial_temperature = match self.initial_temperature {
+            InitialTemperature::Fixed(t) => t,
+            InitialTemperature::Estimated {
+                acceptance,
+                samples,
+            } => estimate_temperature(&current, graph, &self.moves, acceptance, samples, rng),
+        };
+        let mut temperature = initial_temperature;
+        let mut best = current.clone();
+        let mut trace = Vec::new();
+        let mut iterations = 0;
+
+        let exhausted = |iterations: usize| {
+            self.max_iterations.is_some_and(|max| iterations >= max)
+                || self.time_limit.is_some_and(|limit| now.elapsed() >= limit)
+        };
+        while !exhausted(iterations) {
+            let mut accepted = 0;
+            let mut proposed = 0;
+            while proposed < self.epoch && !exhausted(iterations) {
+                let m = random_move(*self.moves.choose(rng).unwrap(), n, rng);
+                let delta = m.delta(&current.route, graph);
+                if delta <= 0. || rng.random_bool((-delta / temperature).exp().min(1.)) {
+                    m.apply(&mut current.route);
+                    current.cost += delta;
+                    accepted += 1;
+                    if current.cost < best.cost - EPSILON {
+                        best.clone_from(&current);
+                    }
+                }
+                proposed += 1;
+                iterations += 1;
+            }
+
+            let acceptance_rate = accepted as f64 / proposed.max(1) as f64;
+            trace.push(Epoch {
+                temperature,
+                acceptance_rate,
+                best_cost: best.cost,
+            });
+
+            temperature = match self.cooling {
+                Cooling::Geometric { alpha } => alpha * temperature,
+                Cooling::Linear => {
+                    let by_iterations = self
+                        .max_iterations
+                        .map_or(0., |max| iterations as f64 / max as f64);
+                    let by_time = self.time_limit.map_or(0., |limit| {
+                        now.elapsed().as_secs_f64() / limit.as_secs_f64()
+                    });
+                    initial_temperature * (1. - by_iterations.max(by_time)).max(0.)
+                }
+                Cooling::LundyMees { beta } => temperature / (1. + beta * temperature),
+                Cooling::Reheating {
+                    alpha,
+                    min_acceptance,
+                    reheat,
+                } => {
+                    if acceptance_rate < min_acceptance {
+                        reheat * initial_temperature
+                    } else {
+                        alpha * temperature
+                    }
+                }
+            };
+        }
+
+        // Recalcula o custo para descartar os erros de arredondamento acumulados nos `delta`.
+        best.cost = Solution::<N>::calculate_cost(&best.route, graph);
+        AnnealingResult {
+            solution: best,
+            initial_temperature,
+            iterations,
+            trace,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact;
+    use crate::local_search::DynSolution;
+    use crate::test_util::{euclidean, is_permutation};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn random_move_test() {
+        let graph = euclidean(6, 1);
+        let route: Vec<usize> = (0..6).collect();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // Os movimentos sorteados são válidos e o `delta` confere com a avaliação completa.
+        for _ in 0..1000 {
+            let neighbourhood = *Annealing::default().moves.choose(&mut rng).unwrap();
+            let m = random_move(neighbourhood, 6, &mut rng);
+            let mut new_route = route.clone();
+            m.apply(&mut new_route);
+
+            assert!(is_permutation(&new_route));
+            assert!(
+                (m.delta(&route, &graph)
+                    - (DynSolution::calculate_cost(&new_route, &graph)
+                        - DynSolution::calculate_cost(&route, &graph)))
+                .abs()
+                    < 1e-9
+            );
+        }
+
+        assert_eq!("oropt".parse(), Ok(Neighbourhood::OrOpt));
+        assert!("3opt".parse::<Neighbourhood>().is_err());
+    }
+
+    #[test]
+    fn annealing_test_1() {
+        let graph = euclidean(10, 2);
+        let optimum = exact::held_karp(&graph).unwrap().cost;
+        let start = DynSolution {
+            route: (0..10).collect(),
+            cost: 0.,
+        };
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for cooling in ["geometric", "linear", "lundy-mees", "reheating"] {
+            let annealing = Annealing {
+                cooling: cooling.parse().unwrap(),
+                max_iterations: Some(20_000),
+                ..Annealing::default()
+            };
+            let result = annealing.run(&start, &graph, &mut rng);
+
+            assert!(is_permutation(&result.solution.route));
+            assert_eq!(
+                result.solution.cost,
+                DynSolution::calculate_cost(&result.solution.route, &graph)
+            );
+            assert!(result.solution.cost >= optimum - 1e-9);
+            assert!(result.solution.cost <= 1.05 * optimum, "{cooling}");
+            assert_eq!(result.iterations, 20_000);
+            assert_eq!(result.trace.len(), 200);
+            assert!(
+                result
+                    .trace
+                    .windows(2)
+                    .all(|w| w[1].best_cost <= w[0].best_cost)
+            );
+        }
+    }
+
+    #[test]
+    fn annealing_test_2() {
+        let graph = euclidean(20, 3);
+        let start = DynSolution {
+            route: (0..20).collect(),
+            cost: 0.,
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let t0 = estimate_temperature(&start, &graph, &[Neighbourhood::TwoOpt], 0.5, 200, &mut rng);
+        assert!(t0 > 0.);
+
+        // O reaquecimento volta para metade da temperatura inicial quando a busca congela.
+        let result = Annealing {
+            cooling: Cooling::Reheating {
+                alpha: 0.5,
+                min_acceptance: 0.05,
+                reheat: 0.5,
+            },
+            initial_temperature: InitialTemperature::Fixed(t0),
+            max_iterations: None,
+            time_limit: Some(Duration::from_millis(50)),
+            ..Annealing::default()
+        }
+        .run(&start, &graph, &mut rng);
+
+        assert_eq!(result.initial_temperature, t0);
+        assert!(result.iterations > 0);
+        assert_eq!(result.trace[0].temperature, t0);
+        assert!(
+            result
+                .trace
+                .iter()
+                .any(|e| (e.temperature - 0.5 * t0).abs() < 1e-12)
+        );
+        assert!(
+            result
+                .trace
+                .iter()
+                .all(|e| (0. ..=1.).contains(&e.acceptance_rate))
+        );
+    }
+}
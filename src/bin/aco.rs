@@ -0,0 +1,57 @@
+use std::time::Instant;
+
+use graphs_algorithms::aco::Aco;
+use graphs_algorithms::{instance, one_tree};
+
+fn main() {
+    let now = Instant::now();
+    let mut rng = rand::rng();
+
+    // Load instance and hyper-params.
+    let args = std::env::args().collect::<Vec<_>>();
+    let g = instance::load(&args[1]).unwrap().matrix; // Path to the instance.
+    let max_iterations = args[2].parse().unwrap(); // Number of iterations.
+    let ants = args[3].parse().unwrap(); // Ants per iteration.
+    // Variant (`as`, `mmas` or `acs`) and the local search applied to each tour (`none` for
+    // none).
+    let variant = args.get(4).map_or("mmas", String::as_str).parse().unwrap();
+    let local_search = args
+        .get(5)
+        .filter(|s| *s != "none")
+        .map(|s| s.parse().unwrap());
+    // Pheromone weight, heuristic weight, evaporation rate, candidate list size and the ACS
+    // greedy choice probability.
+    let default = Aco::default();
+    let param = |i: usize, default: f64| args.get(i).map_or(default, |a| a.parse().unwrap());
+    let alpha = param(6, default.alpha);
+    let beta = param(7, default.beta);
+    let rho = param(8, default.rho);
+    let candidates = args
+        .get(9)
+        .map_or(default.candidates, |a| a.parse().unwrap());
+    let q0 = param(10, default.q0);
+
+    let aco = Aco {
+        variant,
+        ants,
+        alpha,
+        beta,
+        rho,
+        candidates,
+        q0,
+        local_search,
+        max_iterations: Some(max_iterations),
+        time_limit: None,
+    };
+    let result = aco.run(&g, &mut rng);
+
+    // Print best cost, time taken and the gap to the Held-Karp lower bound.
+    let best = result.solution.cost;
+    let elapsed = now.elapsed().as_secs_f64();
+    // A known bound (e.g. computed once by `run.sh`) can be given in `BOUND`.
+    let bound = std::env::var("BOUND").map_or_else(
+        |_| one_tree::lower_bound(&g).value,
+        |bound| bound.parse().unwrap(),
+    );
+    println!("{best} {elapsed} {}", one_tree::gap(best, bound))
+}
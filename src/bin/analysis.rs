@@ -0,0 +1,10 @@
+use graphs_algorithms::{analysis, instance};
+
+fn main() {
+    // Analyse every instance given as argument.
+    for path in std::env::args().skip(1) {
+        let instance = instance::load(&path).unwrap();
+        println!("== {path}");
+        print!("{}", analysis::analyse(&instance.matrix));
+    }
+}
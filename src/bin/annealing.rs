@@ -0,0 +1,54 @@
+use std::time::{Duration, Instant};
+
+use graphs_algorithms::annealing::Annealing;
+use graphs_algorithms::annealing::Neighbourhood;
+use graphs_algorithms::graphs::DistanceMatrix;
+use graphs_algorithms::local_search::DynSolution;
+use graphs_algorithms::{instance, one_tree};
+use rand::seq::SliceRandom;
+
+fn main() {
+    let now = Instant::now();
+    let mut rng = rand::rng();
+
+    // Load instance and hyper-params.
+    let args = std::env::args().collect::<Vec<_>>();
+    let g = instance::load(&args[1]).unwrap().matrix; // Path to the instance.
+    let time_limit = Duration::from_secs_f64(args[2].parse().unwrap()); // Time budget, in seconds.
+    let cooling = args
+        .get(3)
+        .map_or("geometric", String::as_str)
+        .parse()
+        .unwrap(); // `geometric`, `linear`, `lundy-mees` or `reheating`.
+    let moves: Vec<Neighbourhood> = args
+        .get(4)
+        .map_or("swap,shift,2opt,oropt", String::as_str)
+        .split(',')
+        .map(|op| op.parse().unwrap())
+        .collect(); // Moves to pick from.
+
+    // Start from a random route, like the genetic algorithms.
+    let mut route: Vec<usize> = (0..g.node_count()).collect();
+    route.shuffle(&mut rng);
+    let cost = DynSolution::calculate_cost(&route, &g);
+    let annealing = Annealing {
+        moves,
+        cooling,
+        max_iterations: None,
+        time_limit: Some(time_limit),
+        ..Annealing::default()
+    };
+    let best = annealing
+        .run(&DynSolution { route, cost }, &g, &mut rng)
+        .solution
+        .cost;
+
+    // Print best cost, time taken and the gap to the Held-Karp lower bound.
+    let elapsed = now.elapsed().as_secs_f64();
+    // A known bound (e.g. computed once by `run.sh`) can be given in `BOUND`.
+    let bound = std::env::var("BOUND").map_or_else(
+        |_| one_tree::lower_bound(&g).value,
+        |bound| bound.parse().unwrap(),
+    );
+    println!("{best} {elapsed} {}", one_tree::gap(best, bound))
+}
@@ -0,0 +1,8 @@
+use graphs_algorithms::{instance, one_tree};
+
+fn main() {
+    // Print the Held-Karp lower bound of the instance, e.g. to be passed in `BOUND`.
+    let args = std::env::args().collect::<Vec<_>>();
+    let g = instance::load(&args[1]).unwrap().matrix; // Path to the instance.
+    println!("{}", one_tree::lower_bound(&g).value)
+}
@@ -0,0 +1,42 @@
+use std::time::{Duration, Instant};
+
+use graphs_algorithms::exact::{self, BranchAndBound};
+use graphs_algorithms::instance;
+
+fn main() {
+    // Load instance.
+    let args = std::env::args().collect::<Vec<_>>();
+    let instance = instance::load(&args[1]).unwrap(); // Path to the instance.
+    // Time limit, in seconds, for the branch and bound.
+    let time_limit = args
+        .get(2)
+        .map(|s| Duration::from_secs_f64(s.parse().unwrap()));
+    let (g, labels) = (&instance.matrix, &instance.labels);
+
+    let now = Instant::now();
+    if let Ok(s) = exact::held_karp(g) {
+        println!(
+            "- Using dynamic programming... Cost: {} Route: {}",
+            s.cost,
+            s.display(labels)
+        );
+        println!("- Elapsed: {:?}", now.elapsed());
+        return;
+    }
+
+    print!("- Using branch and bound... ");
+    let result = BranchAndBound { time_limit }.solve(g);
+    println!(
+        "Cost: {} Gap: {:.2}% Route: {}",
+        result.solution.cost,
+        100. * result.gap(),
+        result.solution.display(labels)
+    );
+    println!(
+        "- Nodes: {} Optimal: {} Lower bound: {} Elapsed: {:?}",
+        result.nodes,
+        result.optimal,
+        result.lower_bound,
+        now.elapsed()
+    );
+}
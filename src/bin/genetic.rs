@@ -1,30 +1,33 @@
 #![feature(slice_swap_unchecked)]
 
-use std::{array, time::Instant};
+use std::time::Instant;
 
-use csv_macro::graph_from_csv;
+use graphs_algorithms::graphs::{DistanceMatrix, Matrix};
+use graphs_algorithms::{instance, one_tree};
 use rand::{rngs::ThreadRng, seq::SliceRandom};
 
-graph_from_csv!("data/006/data.csv");
+/// A node (city) of the TSP instance.
+type Node = usize;
+
+/// The TSP instance, loaded at runtime.
+type Graph = Matrix;
 
 /// A metric for representing the quality of a solution to the TSP problem.
 type Fit = f64;
 
 /// A candidate solution for the TSP problem.
-type Individual = [Node; NODE_COUNT];
+type Individual = Vec<Node>;
 
 /// Pool of current candidate solutions.
 type Population = [Individual];
 
 /// Fills a population with random solutions (individuals).
 #[inline]
-fn init(rng: &mut ThreadRng, p: &mut Population) {
-    let mut rit = 0..NODE_COUNT;
-    // SAFETY: Range iterator was declared above with NODE_COUNT.
-    let mut r: [usize; NODE_COUNT] = array::from_fn(|_| unsafe { rit.next().unwrap_unchecked() });
+fn init(rng: &mut ThreadRng, g: &Graph, p: &mut Population) {
+    let mut r: Individual = (0..g.node_count()).collect();
     for i in p {
         r.shuffle(rng);
-        *i = r;
+        i.clone_from(&r);
     }
 }
 
@@ -33,8 +36,8 @@ fn init(rng: &mut ThreadRng, p: &mut Population) {
 /// In this case, the sum of edge costs between adjacent nodes in the individual, including a cycle
 /// back to the beginning.
 #[inline]
-fn fit(i: &Individual) -> Fit {
-    i.windows(2).map(|w| g[w[0]][w[1]]).sum::<Fit>() + g[i[NODE_COUNT - 1]][i[0]]
+fn fit(g: &Graph, i: &Individual) -> Fit {
+    i.windows(2).map(|w| g[w[0]][w[1]]).sum::<Fit>() + g[i[i.len() - 1]][i[0]]
 }
 
 /// Executes a crossover between the individuals of two equal halves of a population and
@@ -46,10 +49,10 @@ fn fit(i: &Individual) -> Fit {
 /// the crossover+mutation operation, the population is reshuffled to enable the crossover of
 /// different individuals.
 #[inline]
-fn love(rng: &mut ThreadRng, mrate: f64, p: &mut Population) {
+fn love(rng: &mut ThreadRng, g: &Graph, mrate: f64, p: &mut Population) {
     let (h1, h2) = p.split_at_mut(p.len() / 2);
     for (p1, p2) in h1.iter_mut().zip(h2) {
-        if let Some(i) = cross(p1, p2)
+        if let Some(i) = cross(g, p1, p2)
             && rand::random_bool(mrate)
         {
             mutate([p1, p2][i]);
@@ -62,9 +65,9 @@ fn love(rng: &mut ThreadRng, mrate: f64, p: &mut Population) {
 /// parent with the offspring if the new individual is more fit. Returns the position of the
 /// overwritten parent in the `[p1, p2]` array.
 #[inline]
-fn cross(p1: &mut Individual, p2: &mut Individual) -> Option<usize> {
-    let mut offspring = [0; NODE_COUNT];
-    let mut visited = [false; NODE_COUNT];
+fn cross(g: &Graph, p1: &mut Individual, p2: &mut Individual) -> Option<usize> {
+    let mut offspring = vec![0; p1.len()];
+    let mut visited = vec![false; p1.len()];
     let mut fst = [&p1, &p2][rand::random_range(0..2)][0];
     offspring[0] = fst;
     visited[fst] = true;
@@ -76,15 +79,15 @@ fn cross(p1: &mut Individual, p2: &mut Individual) -> Option<usize> {
         visited[*n] = true;
     }
     [p1, p2].iter_mut().enumerate().find_map(|(i, p)| {
-        (fit(&offspring) < fit(p)).then(|| {
-            **p = offspring;
+        (fit(g, &offspring) < fit(g, p)).then(|| {
+            p.copy_from_slice(&offspring);
             i
         })
     })
 }
 
 /// Helper function to find the first _legitimate node_ after `fst` in the crossover operation ([`cross`]).
-fn legitimate(fst: usize, visited: &mut [bool; NODE_COUNT], i: &Individual) -> Node {
+fn legitimate(fst: usize, visited: &mut [bool], i: &Individual) -> Node {
     if let Some(n) = i.iter().enumerate().find_map(|(k, n)| {
         if *n == fst {
             i[k + 1..].iter().find(|n| !visited[**n])
@@ -94,15 +97,15 @@ fn legitimate(fst: usize, visited: &mut [bool; NODE_COUNT], i: &Individual) -> N
     }) {
         *n
     } else {
-        (0..NODE_COUNT).find(|n| !visited[*n]).unwrap()
+        (0..i.len()).find(|n| !visited[*n]).unwrap()
     }
 }
 
 /// Executes a swap operation in a random contiguous pair of nodes in the individual.
 #[inline]
 fn mutate(i: &mut Individual) {
-    let pos = rand::random_range(0..NODE_COUNT - 1);
-    // SAFETY: pos is at least NODE_COUNT - 1 and NODE_COUNT is greater than 1.
+    let pos = rand::random_range(0..i.len() - 1);
+    // SAFETY: pos is at most len - 2, so pos + 1 is still in bounds.
     unsafe {
         i.swap_unchecked(pos, pos + 1);
     }
@@ -115,29 +118,34 @@ fn main() {
     let now = Instant::now();
     let mut rng = rand::rng();
 
-    // Load hyper-params.
+    // Load instance and hyper-params.
     let args = std::env::args().collect::<Vec<_>>();
-    let itnum: usize = args[1].parse().unwrap(); // Number of iterations (or generations).
-    let psize: usize = args[2].parse().unwrap(); // Size of the population by number of individuals.
-    let mrate: f64 = args[3].parse().unwrap(); // Mutation rate.
+    let g = instance::load(&args[1]).unwrap().matrix; // Path to the instance.
+    let itnum: usize = args[2].parse().unwrap(); // Number of iterations (or generations).
+    let psize: usize = args[3].parse().unwrap(); // Size of the population by number of individuals.
+    let mrate: f64 = args[4].parse().unwrap(); // Mutation rate.
     assert!(psize <= MAX_PSIZE);
 
     // Init population.
-    let p: &mut Population = &mut [[0; NODE_COUNT]; MAX_PSIZE][..psize];
-    init(&mut rng, p);
+    let p: &mut Population = &mut vec![vec![0; g.node_count()]; psize];
+    init(&mut rng, &g, p);
 
     // Make love.
     for _ in 0..itnum {
-        love(&mut rng, mrate, p);
+        love(&mut rng, &g, mrate, p);
     }
 
-    // Print best fitness and time taken.
-    println!(
-        "{} {}",
-        p.iter()
-            .map(fit)
-            .min_by(|x, y| x.total_cmp(y))
-            .unwrap_or(f64::INFINITY),
-        now.elapsed().as_secs_f64()
-    )
+    // Print best fitness, time taken and the gap to the Held-Karp lower bound.
+    let best = p
+        .iter()
+        .map(|i| fit(&g, i))
+        .min_by(|x, y| x.total_cmp(y))
+        .unwrap_or(f64::INFINITY);
+    let elapsed = now.elapsed().as_secs_f64();
+    // A known bound (e.g. computed once by `run.sh`) can be given in `BOUND`.
+    let bound = std::env::var("BOUND").map_or_else(
+        |_| one_tree::lower_bound(&g).value,
+        |bound| bound.parse().unwrap(),
+    );
+    println!("{best} {elapsed} {}", one_tree::gap(best, bound))
 }
@@ -0,0 +1,40 @@
+use std::time::Instant;
+
+use graphs_algorithms::grasp::{Alpha, Grasp};
+use graphs_algorithms::{instance, one_tree};
+
+fn main() {
+    let now = Instant::now();
+    let mut rng = rand::rng();
+
+    // Load instance and hyper-params.
+    let args = std::env::args().collect::<Vec<_>>();
+    let g = instance::load(&args[1]).unwrap().matrix; // Path to the instance.
+    let itnum: usize = args[2].parse().unwrap(); // Number of iterations.
+    let alpha = match args[3].as_str() {
+        "reactive" => Alpha::reactive(),
+        a => Alpha::Fixed(a.parse().unwrap()),
+    }; // Size of the restricted candidate list, in [0, 1], or `reactive`.
+    let construction = args.get(4).map_or("ni", String::as_str).parse().unwrap(); // `nn` or `ni`.
+    let neighbourhood = args.get(5).map_or("2opt", String::as_str).parse().unwrap(); // Local search.
+    let path_relinking = args.get(6).map(|a| a.parse().unwrap()); // Size of the elite pool.
+
+    let grasp = Grasp {
+        construction,
+        alpha,
+        neighbourhood,
+        max_iterations: Some(itnum),
+        time_limit: None,
+        path_relinking,
+    };
+    let best = grasp.run(&g, &mut rng).solution.cost;
+
+    // Print best cost, time taken and the gap to the Held-Karp lower bound.
+    let elapsed = now.elapsed().as_secs_f64();
+    // A known bound (e.g. computed once by `run.sh`) can be given in `BOUND`.
+    let bound = std::env::var("BOUND").map_or_else(
+        |_| one_tree::lower_bound(&g).value,
+        |bound| bound.parse().unwrap(),
+    );
+    println!("{best} {elapsed} {}", one_tree::gap(best, bound))
+}
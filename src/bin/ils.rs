@@ -0,0 +1,52 @@
+use std::time::{Duration, Instant};
+
+use graphs_algorithms::construction::nearest_neighbour;
+use graphs_algorithms::ils::Ils;
+use graphs_algorithms::{instance, one_tree};
+
+fn main() {
+    let now = Instant::now();
+    let mut rng = rand::rng();
+
+    // Load instance and hyper-params.
+    let args = std::env::args().collect::<Vec<_>>();
+    let g = instance::load(&args[1]).unwrap().matrix; // Path to the instance.
+    let time_limit = Duration::from_secs_f64(args[2].parse().unwrap()); // Time budget, in seconds.
+    // Kick (`double-bridge` or `reversal`), acceptance (`better`, `walk`, `restart` or `lsmc`)
+    // and local search.
+    let kick = args
+        .get(3)
+        .map_or("double-bridge", String::as_str)
+        .parse()
+        .unwrap();
+    let acceptance = args
+        .get(4)
+        .map_or("better", String::as_str)
+        .parse()
+        .unwrap();
+    let neighbourhood = args.get(5).map_or("2opt", String::as_str).parse().unwrap();
+
+    let ils = Ils {
+        kick,
+        neighbourhood,
+        acceptance,
+        max_iterations: None,
+        time_limit: Some(time_limit),
+    };
+    let result = ils.run(&nearest_neighbour(&g, 0), &g, &mut rng);
+
+    // Best cost over time, on stderr so the output stays parseable.
+    for record in &result.trace {
+        eprintln!("{} {}", record.elapsed.as_secs_f64(), record.cost);
+    }
+
+    // Print best cost, time taken and the gap to the Held-Karp lower bound.
+    let best = result.solution.cost;
+    let elapsed = now.elapsed().as_secs_f64();
+    // A known bound (e.g. computed once by `run.sh`) can be given in `BOUND`.
+    let bound = std::env::var("BOUND").map_or_else(
+        |_| one_tree::lower_bound(&g).value,
+        |bound| bound.parse().unwrap(),
+    );
+    println!("{best} {elapsed} {}", one_tree::gap(best, bound))
+}
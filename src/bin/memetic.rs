@@ -1,31 +1,33 @@
 #![feature(slice_swap_unchecked)]
 
-use csv_macro::graph_from_csv;
-use graphs_algorithms::local_search::LocalSearch;
-use graphs_algorithms::local_search::Solution;
+use graphs_algorithms::graphs::{DistanceMatrix, Matrix};
+use graphs_algorithms::local_search::{DynSolution, Neighbourhood, Pivoting, Policy, Solution};
+use graphs_algorithms::{instance, one_tree};
 use rand::{rngs::ThreadRng, seq::SliceRandom};
-use std::{array, time::Instant};
+use std::time::Instant;
 
-graph_from_csv!("data/012/data.csv");
+/// A node (city) of the TSP instance.
+type Node = usize;
+
+/// The TSP instance, loaded at runtime.
+type Graph = Matrix;
 
 /// A metric for representing the quality of a solution to the TSP problem.
 type Fit = f64;
 
 /// A candidate solution for the TSP problem.
-type Individual = [Node; NODE_COUNT];
+type Individual = Vec<Node>;
 
 /// Pool of current candidate solutions.
 type Population = [Individual];
 
 /// Fills a population with random solutions (individuals).
 #[inline]
-fn init(rng: &mut ThreadRng, p: &mut Population) {
-    let mut rit = 0..NODE_COUNT;
-    // SAFETY: Range iterator was declared above with NODE_COUNT.
-    let mut r: [usize; NODE_COUNT] = array::from_fn(|_| unsafe { rit.next().unwrap_unchecked() });
+fn init(rng: &mut ThreadRng, g: &Graph, p: &mut Population) {
+    let mut r: Individual = (0..g.node_count()).collect();
     for i in p {
         r.shuffle(rng);
-        *i = r;
+        i.clone_from(&r);
     }
 }
 
@@ -34,17 +36,17 @@ fn init(rng: &mut ThreadRng, p: &mut Population) {
 /// In this case, the sum of edge costs between adjacent nodes in the individual, including a cycle
 /// back to the beginning.
 #[inline]
-fn fit(i: &Individual) -> Fit {
-    i.windows(2).map(|w| g[w[0]][w[1]]).sum::<Fit>() + g[i[NODE_COUNT - 1]][i[0]]
+fn fit(g: &Graph, i: &Individual) -> Fit {
+    i.windows(2).map(|w| g[w[0]][w[1]]).sum::<Fit>() + g[i[i.len() - 1]][i[0]]
 }
 
 /// Executes a Sequential Constructive Crossover (SCX) between two individuals and overwrites a
 /// parent with the offspring if the new individual is more fit. Returns the position of the
 /// overwritten parent in the `[p1, p2]` array.
 #[inline]
-fn cross(p1: &mut Individual, p2: &mut Individual) -> Option<usize> {
-    let mut offspring = [0; NODE_COUNT];
-    let mut visited = [false; NODE_COUNT];
+fn cross(g: &Graph, p1: &mut Individual, p2: &mut Individual) -> Option<usize> {
+    let mut offspring = vec![0; p1.len()];
+    let mut visited = vec![false; p1.len()];
     let mut fst = [&p1, &p2][rand::random_range(0..2)][0];
     offspring[0] = fst;
     visited[fst] = true;
@@ -56,15 +58,15 @@ fn cross(p1: &mut Individual, p2: &mut Individual) -> Option<usize> {
         visited[*n] = true;
     }
     [p1, p2].iter_mut().enumerate().find_map(|(i, p)| {
-        (fit(&offspring) < fit(p)).then(|| {
-            **p = offspring;
+        (fit(g, &offspring) < fit(g, p)).then(|| {
+            p.copy_from_slice(&offspring);
             i
         })
     })
 }
 
 /// Helper function to find the first _legitimate node_ after `fst` in the crossover operation ([`cross`]).
-fn legitimate(fst: usize, visited: &mut [bool; NODE_COUNT], i: &Individual) -> Node {
+fn legitimate(fst: usize, visited: &mut [bool], i: &Individual) -> Node {
     if let Some(n) = i.iter().enumerate().find_map(|(k, n)| {
         if *n == fst {
             i[k + 1..].iter().find(|n| !visited[**n])
@@ -74,15 +76,15 @@ fn legitimate(fst: usize, visited: &mut [bool; NODE_COUNT], i: &Individual) -> N
     }) {
         *n
     } else {
-        (0..NODE_COUNT).find(|n| !visited[*n]).unwrap()
+        (0..i.len()).find(|n| !visited[*n]).unwrap()
     }
 }
 
 /// Executes a swap operation in a random contiguous pair of nodes in the individual.
 #[inline]
 fn mutate(i: &mut Individual) {
-    let pos = rand::random_range(0..NODE_COUNT - 1);
-    // SAFETY: pos is at least NODE_COUNT - 1 and NODE_COUNT is greater than 1.
+    let pos = rand::random_range(0..i.len() - 1);
+    // SAFETY: pos is at most len - 2, so pos + 1 is still in bounds.
     unsafe {
         i.swap_unchecked(pos, pos + 1);
     }
@@ -92,13 +94,13 @@ fn mutate(i: &mut Individual) {
 const MAX_PSIZE: usize = 200;
 
 /// Função utilitária que converte um Individual para uma Solution a fim de utilizar as buscas locais implementadas.
-fn individual_to_solution(p: &Individual) -> Solution<NODE_COUNT> {
+fn individual_to_solution(g: &Graph, p: &Individual) -> DynSolution {
     let mut s = Solution {
         route: vec![],
         cost: 0.0,
     };
     s.route = p.to_vec();
-    s.cost = Solution::calculate_cost(&s.route, &g);
+    s.cost = DynSolution::calculate_cost(&s.route, g);
     s
 }
 
@@ -106,24 +108,39 @@ fn individual_to_solution(p: &Individual) -> Solution<NODE_COUNT> {
 /// A diferença em relação à `love` original está após a mutação, onde sorteamos de forma aleatória alguma das buscas locais implementadas
 /// e aplicamos no indivíduo.
 /// Ao fim, a população é reorganizada aleatoriamente para permitir novos cruzamentos na próxima geração.
+///
+/// A busca local de cada geração é sorteada entre as vizinhanças de `ops`. Caso `pivoting` seja
+/// informado, todas as buscas locais usam essa regra de pivoteamento até um ótimo local; caso
+/// contrário, cada busca usa a sua política padrão.
 #[inline]
-fn love_w_gen_mods(rng: &mut ThreadRng, mrate: f64, p: &mut Population) {
-    let rnd_op = rand::random_range(1..=100);
+fn love_w_gen_mods(
+    rng: &mut ThreadRng,
+    g: &Graph,
+    mrate: f64,
+    pivoting: Option<Pivoting>,
+    ops: &[Neighbourhood],
+    p: &mut Population,
+) {
+    let op = ops[rand::random_range(0..ops.len())];
     let (h1, h2) = p.split_at_mut(p.len() / 2);
     for (p1, p2) in h1.iter_mut().zip(h2) {
-        if let Some(i) = cross(p1, p2)
+        if let Some(i) = cross(g, p1, p2)
             && rand::random_bool(mrate)
         {
             let offspring = &mut [p1, p2][i];
             let s = {
                 mutate(offspring);
-                individual_to_solution(offspring)
+                individual_to_solution(g, offspring)
             };
-            let s = match rnd_op {
-                1..25 => s.shift(&g, s.route[0]),
-                25..50 => s.swap(&g, s.route[0]),
-                50..75 => s.two_opt(&g),
-                _ => s.or_opt(&g),
+            let s = match pivoting {
+                None => op.search(&s, g, s.route[0]),
+                Some(pivoting) => {
+                    let policy = Policy {
+                        pivoting,
+                        max_moves: None,
+                    };
+                    op.search_with(&s, g, s.route[0], policy).solution
+                }
             };
             offspring.copy_from_slice(&s.route);
         }
@@ -136,29 +153,44 @@ fn main() {
     let now = Instant::now();
     let mut rng = rand::rng();
 
-    // Load hyper-params.
+    // Load instance and hyper-params.
     let args = std::env::args().collect::<Vec<_>>();
-    let itnum: usize = args[1].parse().unwrap(); // Number of iterations (or generations).
-    let psize: usize = args[2].parse().unwrap(); // Size of the population by number of individuals.
-    let mrate: f64 = args[3].parse().unwrap(); // Mutation rate.
+    let g = instance::load(&args[1]).unwrap().matrix; // Path to the instance.
+    let itnum: usize = args[2].parse().unwrap(); // Number of iterations (or generations).
+    let psize: usize = args[3].parse().unwrap(); // Size of the population by number of individuals.
+    let mrate: f64 = args[4].parse().unwrap(); // Mutation rate.
+    let pivoting: Option<Pivoting> = args
+        .get(5)
+        .filter(|a| *a != "default")
+        .map(|a| a.parse().unwrap()); // Pivoting rule (`first`, `best` or `default`).
+    let ops: Vec<Neighbourhood> = args
+        .get(6)
+        .map_or("shift,swap,2opt,oropt", String::as_str)
+        .split(',')
+        .map(|op| op.parse().unwrap())
+        .collect(); // Local searches to pick from (e.g. `2opt,oropt,3opt,or3opt`).
     assert!(psize <= MAX_PSIZE);
 
     // Init population.
-    let p: &mut Population = &mut [[0; NODE_COUNT]; MAX_PSIZE][..psize];
-    init(&mut rng, p);
+    let p: &mut Population = &mut vec![vec![0; g.node_count()]; psize];
+    init(&mut rng, &g, p);
 
     // Make love with some genetics manipulation.
     for _ in 0..itnum {
-        love_w_gen_mods(&mut rng, mrate, p);
+        love_w_gen_mods(&mut rng, &g, mrate, pivoting, &ops, p);
     }
 
-    // Print best fitness and time taken.
-    println!(
-        "{} {}",
-        p.iter()
-            .map(fit)
-            .min_by(|x, y| x.total_cmp(y))
-            .unwrap_or(f64::INFINITY),
-        now.elapsed().as_secs_f64()
-    )
+    // Print best fitness, time taken and the gap to the Held-Karp lower bound.
+    let best = p
+        .iter()
+        .map(|i| fit(&g, i))
+        .min_by(|x, y| x.total_cmp(y))
+        .unwrap_or(f64::INFINITY);
+    let elapsed = now.elapsed().as_secs_f64();
+    // A known bound (e.g. computed once by `run.sh`) can be given in `BOUND`.
+    let bound = std::env::var("BOUND").map_or_else(
+        |_| one_tree::lower_bound(&g).value,
+        |bound| bound.parse().unwrap(),
+    );
+    println!("{best} {elapsed} {}", one_tree::gap(best, bound))
 }
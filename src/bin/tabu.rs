@@ -0,0 +1,43 @@
+use std::time::Instant;
+
+use graphs_algorithms::construction::nearest_neighbour;
+use graphs_algorithms::tabu::Tabu;
+use graphs_algorithms::{instance, one_tree};
+
+fn main() {
+    let now = Instant::now();
+    let mut rng = rand::rng();
+
+    // Load instance and hyper-params.
+    let args = std::env::args().collect::<Vec<_>>();
+    let g = instance::load(&args[1]).unwrap().matrix; // Path to the instance.
+    let itnum: usize = args[2].parse().unwrap(); // Number of iterations.
+    // Neighbourhood (`swap` or `2opt`), tabu attribute (`edges` or `positions`) and tenure (`N`,
+    // `MIN-MAX` or `reactive`).
+    let neighbourhood = args.get(3).map_or("2opt", String::as_str).parse().unwrap();
+    let attribute = args.get(4).map_or("edges", String::as_str).parse().unwrap();
+    let tenure = args.get(5).map_or("10", String::as_str).parse().unwrap();
+    let diversification = args.get(6).map(|a| a.parse().unwrap()); // Weight of the frequency penalty.
+
+    let tabu = Tabu {
+        neighbourhood,
+        attribute,
+        tenure,
+        diversification,
+        max_iterations: Some(itnum),
+        time_limit: None,
+    };
+    let best = tabu
+        .run(&nearest_neighbour(&g, 0), &g, &mut rng)
+        .solution
+        .cost;
+
+    // Print best cost, time taken and the gap to the Held-Karp lower bound.
+    let elapsed = now.elapsed().as_secs_f64();
+    // A known bound (e.g. computed once by `run.sh`) can be given in `BOUND`.
+    let bound = std::env::var("BOUND").map_or_else(
+        |_| one_tree::lower_bound(&g).value,
+        |bound| bound.parse().unwrap(),
+    );
+    println!("{best} {elapsed} {}", one_tree::gap(best, bound))
+}
@@ -0,0 +1,404 @@
+//! Listas de vizinhos candidatos e buscas locais com *don't-look bits*.
+//!
+//! As vizinhanças de [`LocalSearch`](crate::local_search::LocalSearch) avaliam todos os pares de
+//! posições da rota, o que custa O(n²) por movimento. Aqui, cada vértice guarda apenas os seus `k`
+//! vizinhos mais próximos em uma [`CandidateList`], e as buscas só avaliam movimentos que inserem
+//! uma aresta entre um vértice e um dos seus candidatos. Além disso, cada vértice tem um
+//! *don't-look bit*: vértices cuja vizinhança não trouxe melhora deixam de ser examinados até que
+//! um movimento altere uma das suas arestas.
+//!
+//! As buscas assumem custos simétricos e sempre aplicam o primeiro movimento de melhora
+//! encontrado.
+
+use std::collections::VecDeque;
+
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{Descent, Move, Solution};
+
+/// Melhora mínima para que um movimento seja aplicado.
+const EPSILON: f64 = 1e-9;
+
+/// Os `k` vizinhos mais próximos de cada vértice, do mais próximo para o mais distante.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandidateList {
+    neighbours: Vec<Vec<usize>>,
+}
+
+impl CandidateList {
+    /// Calcula os `k` vizinhos mais próximos de cada vértice de `graph`.
+    pub fn new<G: DistanceMatrix + ?Sized>(graph: &G, k: usize) -> Self {
+        Self::from_fn(graph.node_count(), k, |u, v| graph.dist(u, v))
+    }
+
+    /// Calcula os `k` vizinhos mais próximos de cada um dos vértices `0..n`, com custos dados por
+    /// `dist`.
+    pub fn from_fn(n: usize, k: usize, dist: impl Fn(usize, usize) -> f64) -> Self {
+        let k = k.min(n.saturating_sub(1));
+        let neighbours = (0..n)
+            .map(|u| {
+                let cmp = |a: &usize, b: &usize| dist(u, *a).total_cmp(&dist(u, *b));
+                let mut c: Vec<usize> = (0..n).filter(|&v| v != u).collect();
+                if k < c.len() {
+                    c.select_nth_unstable_by(k, cmp);
+                    c.truncate(k);
+                }
+                c.sort_by(cmp);
+                c
+            })
+            .collect();
+        Self { neighbours }
+    }
+
+    /// Os candidatos de `v`, do mais próximo para o mais distante.
+    pub fn neighbours(&self, v: usize) -> &[usize] {
+        &self.neighbours[v]
+    }
+
+    /// Quantidade de vértices.
+    pub fn len(&self) -> usize {
+        self.neighbours.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.neighbours.is_empty()
+    }
+}
+
+/// 2-opt restrito às arestas candidatas, até um ótimo local.
+pub(crate) fn two_opt<const N: usize, G: DistanceMatrix + ?Sized>(
+    solution: &Solution<N>,
+    graph: &G,
+    candidates: &CandidateList,
+) -> Descent<Solution<N>> {
+    search(solution, graph, |tour, a| {
+        two_opt_step(tour, a, graph, candidates)
+    })
+}
+
+/// Or-opt (trechos de até `max_len` vértices) restrito às arestas candidatas, até um ótimo
+/// local. Com `max_len = 1`, é a vizinhança shift.
+pub(crate) fn or_opt<const N: usize, G: DistanceMatrix + ?Sized>(
+    solution: &Solution<N>,
+    graph: &G,
+    candidates: &CandidateList,
+    max_len: usize,
+) -> Descent<Solution<N>> {
+    search(solution, graph, |tour, a| {
+        (1..=max_len).any(|len| or_opt_step(tour, a, len, graph, candidates))
+    })
+}
+
+/// Examina os vértices ativos até que nenhum deles leve a uma melhora. `step` tenta aplicar um
+/// movimento de melhora envolvendo o vértice dado e retorna se conseguiu.
+fn search<const N: usize, G: DistanceMatrix + ?Sized>(
+    solution: &Solution<N>,
+    graph: &G,
+    mut step: impl FnMut(&mut Tour, usize) -> bool,
+) -> Descent<Solution<N>> {
+    let mut tour = Tour::new(&solution.route, graph.node_count());
+    let mut moves = 0;
+
+    if tour.route.len() >= 5 {
+        while let Some(a) = tour.pop() {
+            if step(&mut tour, a) {
+                moves += 1;
+                tour.wake(a);
+            }
+        }
+    }
+
+    let route = tour.into_route(solution.route.first().copied());
+    let cost = Solution::<N>::calculate_cost(&route, graph);
+    Descent {
+        solution: Solution { route, cost },
+        moves,
+        // Ótimo local da vizinhança restrita aos candidatos, não da completa.
+        local_optimum: true,
+    }
+}
+
+fn two_opt_step<G: DistanceMatrix + ?Sized>(
+    tour: &mut Tour,
+    a: usize,
+    graph: &G,
+    candidates: &CandidateList,
+) -> bool {
+    for forward in [true, false] {
+        let b = tour.next(a, forward);
+        let dab = graph.dist(a, b);
+        for &c in candidates.neighbours(a) {
+            // Toda troca de melhora tem `d(a, c) < d(a, b)` para uma das suas extremidades `a`.
+            let dac = graph.dist(a, c);
+            if dac >= dab {
+                break;
+            }
+            if !tour.contains(c) || c == b {
+                continue;
+            }
+            let d = tour.next(c, forward);
+            if d == a {
+                continue;
+            }
+
+            let delta = dac + graph.dist(b, d) - dab - graph.dist(c, d);
+            if delta < -EPSILON {
+                if forward {
+                    tour.reverse(tour.pos[b], tour.pos[c]);
+                } else {
+                    tour.reverse(tour.pos[c], tour.pos[b]);
+                }
+                for v in [b, c, d] {
+                    tour.wake(v);
+                }
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn or_opt_step<G: DistanceMatrix + ?Sized>(
+    tour: &mut Tour,
+    a: usize,
+    len: usize,
+    graph: &G,
+    candidates: &CandidateList,
+) -> bool {
+    let n = tour.route.len();
+    if len + 2 > n {
+        return false;
+    }
+    // Trechos que começam ou terminam em `a`.
+    let pa = tour.pos[a];
+    let starts = [Some(pa), (len > 1).then(|| (pa + n + 1 - len) % n)];
+
+    for start in starts.into_iter().flatten() {
+        let (s1, s2) = (tour.route[start], tour.route[(start + len - 1) % n]);
+        let p = tour.route[(start + n - 1) % n];
+        let nx = tour.route[(start + len) % n];
+        let gain = graph.dist(p, s1) + graph.dist(s2, nx) - graph.dist(p, nx);
+        if gain <= EPSILON {
+            continue;
+        }
+        let inside = |tour: &Tour, v: usize| (tour.pos[v] + n - start) % n < len;
+
+        // Insere o trecho entre `c` e o seu sucessor, com a aresta `(c, s1)`, ou entre o
+        // predecessor de `c` e `c`, com a aresta `(s2, c)`.
+        for (end, forward) in [(s1, true), (s2, false)] {
+            for &c in candidates.neighbours(end) {
+                if !tour.contains(c) || inside(tour, c) {
+                    continue;
+                }
+                let e = tour.next(c, forward);
+                if inside(tour, e) {
+                    continue;
+                }
+
+                let delta = graph.dist(c, end) + graph.dist(if forward { s2 } else { s1 }, e)
+                    - graph.dist(c, e)
+                    - gain;
+                if delta < -EPSILON {
+                    tour.move_segment(start, len, c, forward);
+                    for v in [p, nx, s1, s2, c, e] {
+                        tour.wake(v);
+                    }
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Rota com a posição de cada vértice e a fila de vértices ativos (com o *don't-look bit*
+/// desligado).
+struct Tour {
+    route: Vec<usize>,
+    /// Posição de cada vértice do grafo na rota, ou `usize::MAX` se ele não estiver na rota.
+    pos: Vec<usize>,
+    queue: VecDeque<usize>,
+    queued: Vec<bool>,
+}
+
+impl Tour {
+    fn new(route: &[usize], node_count: usize) -> Self {
+        let size = route
+            .iter()
+            .map(|&v| v + 1)
+            .max()
+            .unwrap_or(0)
+            .max(node_count);
+        let mut pos = vec![usize::MAX; size];
+        let mut queued = vec![false; size];
+        for (i, &v) in route.iter().enumerate() {
+            pos[v] = i;
+            queued[v] = true;
+        }
+        Self {
+            route: route.to_vec(),
+            pos,
+            queue: route.iter().copied().collect(),
+            queued,
+        }
+    }
+
+    fn contains(&self, v: usize) -> bool {
+        self.pos.get(v).is_some_and(|&p| p != usize::MAX)
+    }
+
+    /// Sucessor de `v` na orientação `forward` (ou predecessor, na orientação contrária).
+    fn next(&self, v: usize, forward: bool) -> usize {
+        let n = self.route.len();
+        let step = if forward { 1 } else { n - 1 };
+        self.route[(self.pos[v] + step) % n]
+    }
+
+    /// Desliga o *don't-look bit* de `v`.
+    fn wake(&mut self, v: usize) {
+        if !self.queued[v] {
+            self.queued[v] = true;
+            self.queue.push_back(v);
+        }
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        let v = self.queue.pop_front()?;
+        self.queued[v] = false;
+        Some(v)
+    }
+
+    /// Inverte o trecho circular que vai da posição `i` até a posição `j`. Como o tour é um
+    /// ciclo, inverte o complemento quando ele for menor.
+    fn reverse(&mut self, i: usize, j: usize) {
+        let n = self.route.len();
+        let len = (j + n - i) % n + 1;
+        if 2 * len > n {
+            self.reverse_range((j + 1) % n, n - len);
+        } else {
+            self.reverse_range(i, len);
+        }
+    }
+
+    fn reverse_range(&mut self, start: usize, len: usize) {
+        let n = self.route.len();
+        for s in 0..len / 2 {
+            let (a, b) = ((start + s) % n, (start + len - 1 - s) % n);
+            self.route.swap(a, b);
+            self.pos[self.route[a]] = a;
+            self.pos[self.route[b]] = b;
+        }
+    }
+
+    /// Move o trecho circular de `len` vértices que começa na posição `start` para logo depois
+    /// de `c` (ou logo antes, na orientação contrária).
+    fn move_segment(&mut self, mut start: usize, len: usize, c: usize, forward: bool) {
+        let n = self.route.len();
+        if start + len > n {
+            // O trecho dá a volta no fim da rota: gira a rota para que ele comece na posição 0.
+            self.route.rotate_left(start);
+            for (i, &v) in self.route.iter().enumerate() {
+                self.pos[v] = i;
+            }
+            start = 0;
+        }
+
+        let target = self.pos[c] + usize::from(forward);
+        let to = if target > start { target - len } else { target };
+        Move::OrOpt { start, len, to }.apply(&mut self.route);
+        for i in start.min(target)..(start + len).max(target) {
+            self.pos[self.route[i]] = i;
+        }
+    }
+
+    /// A rota final, começando por `first` caso ele seja informado.
+    fn into_route(mut self, first: Option<usize>) -> Vec<usize> {
+        if let Some(first) = first {
+            let p = self.pos[first];
+            self.route.rotate_left(p);
+        }
+        self.route
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::Matrix;
+    use crate::local_search::{DynSolution, LocalSearch, Policy};
+    use crate::test_util::is_permutation;
+    use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+    fn random_instance(n: usize, seed: u64) -> (Matrix, DynSolution) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let points: Vec<(f64, f64)> = (0..n)
+            .map(|_| (rng.random_range(0.0..1000.0), rng.random_range(0.0..1000.0)))
+            .collect();
+        let graph = Matrix::from_fn(n, |u, v| {
+            let (dx, dy) = (points[u].0 - points[v].0, points[u].1 - points[v].1);
+            (dx * dx + dy * dy).sqrt()
+        });
+        let mut route: Vec<usize> = (0..n).collect();
+        route.shuffle(&mut rng);
+        let cost = DynSolution::calculate_cost(&route, &graph);
+        (graph, DynSolution { route, cost })
+    }
+
+    #[test]
+    fn candidate_list_test() {
+        let graph = vec![
+            vec![0.0, 4.0, 1.0, 3.0],
+            vec![4.0, 0.0, 2.0, 5.0],
+            vec![1.0, 2.0, 0.0, 6.0],
+            vec![3.0, 5.0, 6.0, 0.0],
+        ];
+
+        let candidates = CandidateList::new(&graph, 2);
+        assert_eq!(candidates.len(), 4);
+        assert_eq!(candidates.neighbours(0), [2, 3]);
+        assert_eq!(candidates.neighbours(1), [2, 0]);
+        assert_eq!(candidates.neighbours(3), [0, 1]);
+        assert_eq!(CandidateList::new(&graph, 10).neighbours(2), [0, 1, 3]);
+    }
+
+    #[test]
+    fn two_opt_candidates_test() {
+        // Com listas completas, o resultado é um ótimo local do 2-opt sem restrições.
+        let (graph, solution) = random_instance(60, 1);
+        let d = solution.two_opt_with_candidates(&graph, &CandidateList::new(&graph, 59));
+        assert!(is_permutation(&d.solution.route));
+        assert_eq!(d.solution.route[0], solution.route[0]);
+        assert_eq!(
+            d.solution
+                .two_opt_with(&graph, Policy::first_improvement())
+                .moves,
+            0
+        );
+
+        let (graph, solution) = random_instance(500, 2);
+        let d = solution.two_opt_with_candidates(&graph, &CandidateList::new(&graph, 8));
+        assert!(is_permutation(&d.solution.route));
+        assert!(d.solution.cost < solution.cost / 5.);
+    }
+
+    #[test]
+    fn or_opt_candidates_test() {
+        let (graph, solution) = random_instance(40, 3);
+        let candidates = CandidateList::new(&graph, 39);
+
+        let d = solution.or_opt_with_candidates(&graph, &candidates);
+        assert!(is_permutation(&d.solution.route));
+        assert!(d.moves > 0);
+        assert_eq!(d.solution.or_opt_with(&graph, Policy::default()).moves, 0);
+
+        let d = solution.shift_with_candidates(&graph, &candidates);
+        assert!(is_permutation(&d.solution.route));
+        for start in 0..40 {
+            assert_eq!(
+                d.solution
+                    .shift_with(&graph, start, Policy::default())
+                    .moves,
+                0
+            );
+        }
+    }
+}
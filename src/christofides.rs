@@ -0,0 +1,215 @@
+//! Algoritmo de Christofides.
+//!
+//! Em instâncias métricas, o ciclo construído custa no máximo 1,5 vez o ótimo:
+//! 1. calcula uma árvore geradora mínima (AGM) do grafo;
+//! 2. calcula um emparelhamento perfeito de custo mínimo entre os vértices de grau ímpar da AGM;
+//! 3. a união da AGM com o emparelhamento é um multigrafo com todos os graus pares, que possui um
+//!    circuito euleriano;
+//! 4. o circuito euleriano vira um ciclo hamiltoniano pulando os vértices repetidos (*shortcut*),
+//!    o que não aumenta o custo quando vale a desigualdade triangular.
+//!
+//! O emparelhamento exato é feito por programação dinâmica sobre subconjuntos, que custa
+//! O(2^k · k) para `k` vértices ímpares. Acima de [`EXACT_MATCHING_LIMIT`] vértices ímpares, é
+//! usado um emparelhamento guloso (pares de menor custo primeiro), e a garantia de 1,5 deixa de
+//! valer.
+
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{DynSolution, Solution};
+use crate::mst;
+
+/// Quantidade máxima de vértices de grau ímpar para o emparelhamento exato.
+pub const EXACT_MATCHING_LIMIT: usize = 20;
+
+/// Resultado do algoritmo de Christofides.
+#[derive(Clone)]
+pub struct Christofides {
+    pub solution: DynSolution,
+    /// Se o emparelhamento usado foi o de custo mínimo (e não o guloso).
+    pub exact_matching: bool,
+}
+
+/// Executa o algoritmo de Christofides sobre `graph`, assumindo custos simétricos. A rota
+/// retornada começa pelo vértice 0.
+pub fn christofides<G: DistanceMatrix + ?Sized>(graph: &G) -> Christofides {
+    build(graph, EXACT_MATCHING_LIMIT)
+}
+
+fn build<G: DistanceMatrix + ?Sized>(graph: &G, exact_limit: usize) -> Christofides {
+    let n = graph.node_count();
+    let mut edges = mst::prim(graph).edges;
+
+    let mut degree = vec![0; n];
+    for &(u, v) in &edges {
+        degree[u] += 1;
+        degree[v] += 1;
+    }
+    let odd: Vec<usize> = (0..n).filter(|&v| degree[v] % 2 == 1).collect();
+    let exact_matching = odd.len() <= exact_limit;
+    edges.extend(if exact_matching {
+        exact_matching_of(graph, &odd)
+    } else {
+        greedy_matching_of(graph, &odd)
+    });
+
+    // Pula os vértices repetidos do circuito euleriano.
+    let mut visited = vec![false; n];
+    let route: Vec<usize> = euler_circuit(n, &edges)
+        .into_iter()
+        .filter(|&v| !std::mem::replace(&mut visited[v], true))
+        .collect();
+    let cost = DynSolution::calculate_cost(&route, graph);
+
+    Christofides {
+        solution: Solution { route, cost },
+        exact_matching,
+    }
+}
+
+/// Emparelhamento perfeito de custo mínimo entre os vértices `odd`, por programação dinâmica:
+/// `cost[mask]` é o menor custo para emparelhar os vértices do subconjunto `mask`, e o vértice de
+/// menor índice de `mask` é sempre emparelhado com algum outro vértice de `mask`.
+fn exact_matching_of<G: DistanceMatrix + ?Sized>(graph: &G, odd: &[usize]) -> Vec<(usize, usize)> {
+    let k = odd.len();
+    let full = (1usize << k) - 1;
+    let mut cost = vec![f64::INFINITY; full + 1];
+    let mut pair = vec![0u8; full + 1];
+    cost[0] = 0.;
+
+    for mask in 1..=full {
+        if mask.count_ones() % 2 == 1 {
+            continue;
+        }
+        let i = mask.trailing_zeros() as usize;
+        for j in (i + 1..k).filter(|&j| mask & (1 << j) != 0) {
+            let c = cost[mask & !(1 << i) & !(1 << j)] + graph.dist(odd[i], odd[j]);
+            if c < cost[mask] {
+                cost[mask] = c;
+                pair[mask] = j as u8;
+            }
+        }
+    }
+
+    let mut matching = Vec::with_capacity(k / 2);
+    let mut mask = full;
+    while mask != 0 {
+        let i = mask.trailing_zeros() as usize;
+        let j = pair[mask] as usize;
+        matching.push((odd[i], odd[j]));
+        mask &= !(1 << i) & !(1 << j);
+    }
+    matching
+}
+
+/// Emparelhamento guloso entre os vértices `odd`: aceita os pares de menor custo primeiro.
+fn greedy_matching_of<G: DistanceMatrix + ?Sized>(graph: &G, odd: &[usize]) -> Vec<(usize, usize)> {
+    let mut pairs: Vec<(usize, usize)> = odd
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &u)| odd[i + 1..].iter().map(move |&v| (u, v)))
+        .collect();
+    pairs.sort_by(|&(a, b), &(c, d)| graph.dist(a, b).total_cmp(&graph.dist(c, d)));
+
+    let mut matched = vec![false; graph.node_count()];
+    pairs
+        .into_iter()
+        .filter(|&(u, v)| {
+            let free = !matched[u] && !matched[v];
+            if free {
+                (matched[u], matched[v]) = (true, true);
+            }
+            free
+        })
+        .collect()
+}
+
+/// Circuito euleriano do multigrafo conexo `edges`, com todos os graus pares, a partir do
+/// vértice 0 (algoritmo de Hierholzer).
+fn euler_circuit(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut incident: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (e, &(u, v)) in edges.iter().enumerate() {
+        incident[u].push(e);
+        incident[v].push(e);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut stack = vec![0];
+    let mut circuit = Vec::with_capacity(edges.len() + 1);
+    while let Some(&u) = stack.last() {
+        match incident[u].pop() {
+            Some(e) if used[e] => {}
+            Some(e) => {
+                used[e] = true;
+                let (a, b) = edges[e];
+                stack.push(if a == u { b } else { a });
+            }
+            None => circuit.extend(stack.pop()),
+        }
+    }
+    circuit.reverse();
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::Matrix;
+    use crate::test_util::{euclidean, is_permutation};
+
+    #[test]
+    fn christofides_test_1() {
+        // Caminho 0 - 1 - 2 - 3 com custos unitários e distâncias do caminho nas demais arestas.
+        let graph = Matrix::from_fn(4, |u, v| u.abs_diff(v) as f64);
+
+        let result = christofides(&graph);
+
+        assert!(result.exact_matching);
+        let route = &result.solution.route;
+        assert!(route == &[0, 1, 2, 3] || route == &[0, 3, 2, 1]);
+        assert_eq!(result.solution.cost, 6.0);
+    }
+
+    #[test]
+    fn christofides_test_2() {
+        let graph = euclidean(30, 5);
+        let tree = mst::prim(&graph).edges;
+        let mut degree = [0; 30];
+        for &(u, v) in &tree {
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+        let odd: Vec<usize> = (0..30).filter(|&v| degree[v] % 2 == 1).collect();
+        let weight = |edges: &[(usize, usize)]| -> f64 {
+            edges.iter().map(|&(u, v)| graph.dist(u, v)).sum()
+        };
+
+        // Em uma instância métrica, o atalho não custa mais que o circuito euleriano.
+        let exact = christofides(&graph);
+        assert!(exact.exact_matching);
+        assert!(is_permutation(&exact.solution.route));
+        assert_eq!(exact.solution.route[0], 0);
+        assert!(exact.solution.cost <= weight(&tree) + weight(&exact_matching_of(&graph, &odd)));
+
+        let greedy = build(&graph, 0);
+        assert!(!greedy.exact_matching);
+        assert!(is_permutation(&greedy.solution.route));
+        assert!(greedy.solution.cost <= weight(&tree) + weight(&greedy_matching_of(&graph, &odd)));
+    }
+
+    #[test]
+    fn matching_test() {
+        let graph = vec![
+            vec![0.0, 2.0, 5.0, 10.0],
+            vec![2.0, 0.0, 1.0, 5.0],
+            vec![5.0, 1.0, 0.0, 2.0],
+            vec![10.0, 5.0, 2.0, 0.0],
+        ];
+        let odd = [0, 1, 2, 3];
+
+        // O guloso escolhe o par mais barato, (1, 2), e é obrigado a usar (0, 3).
+        assert_eq!(exact_matching_of(&graph, &odd), [(0, 1), (2, 3)]);
+        assert_eq!(greedy_matching_of(&graph, &odd), [(1, 2), (0, 3)]);
+    }
+}
@@ -0,0 +1,806 @@
+//! Heurísticas construtivas para o Caixeiro Viajante.
+//!
+//! As heurísticas recebem qualquer matriz de distâncias ([`DistanceMatrix`]) e retornam uma
+//! [`DynSolution`] com a rota construída e o seu custo. As versões `*_with_*` refinam a rota
+//! construída com uma busca local de [`LocalSearch`].
+
+use std::cmp::Ordering;
+
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{DynSolution, LocalSearch, Solution};
+
+/// Implementação da heurística do Vizinho Mais Próximo (Nearest Neighbor), um algoritmo
+/// guloso que gera um caminho para o Problema do Caixeiro Viajante.
+///
+/// # Argumentos
+/// - `graph`: grafo representado em matriz de adjacência;
+/// - `start`: nó inicial por onde o caminho começará.
+///
+/// # Saída
+/// Retorna uma `Solution`, com a rota encontrada e o custo dela.
+///
+/// # Detalhes de funcionamento
+/// 1. Cria um vetor booleano `visited`, onde é possível verificar em O(1) se um
+///    vértice já foi visitado. Todos começam como "não visitados".
+/// 2. Marca o vértice inicial como visitado.
+/// 3. Cria um contador com a quantidade de vértices visitados. Isso permite
+///    determinar o fim do loop sem precisar usar uma checagem O(n).
+/// 4. Enquanto houver vértices não visitados, itera sobre eles na matriz em busca
+///    da aresta de menor custo. Ao encontrá-la, adiciona seu vértice adjacente ao
+///    caminho, marca-o como visitado e incrementa o contador.
+/// 5. Ao fim do loop, adiciona ao custo a aresta que liga o último vértice do
+///    caminho ao vértice inicial, sem adicionar este novamente ao caminho.
+/// 6. Retorna o caminho encontrado e o custo total.
+///
+/// Observação: este algoritmo **não** insere o vértice inicial no fim do caminho,
+/// pois assume-se que há um ciclo hamiltoniano implícito; portanto, adicionar o
+/// vértice inicial novamente não é necessário.
+pub fn nearest_neighbour<G: DistanceMatrix + ?Sized>(graph: &G, start: usize) -> DynSolution {
+    let mut visited: Vec<bool> = vec![false; graph.node_count()];
+    let mut path: Vec<usize> = Vec::new();
+    path.push(start);
+
+    let mut cost: f64 = 0.0;
+    let mut current_node = start;
+    visited[current_node] = true;
+
+    let mut visited_count = 1;
+
+    let mut better_cost;
+    let mut next_on_path: Option<usize> = None;
+
+    while visited_count != graph.node_count() {
+        better_cost = f64::INFINITY;
+
+        for (i, val) in visited.iter().enumerate() {
+            if *val || current_node == i {
+                continue;
+            }
+
+            if graph.dist(current_node, i) < better_cost {
+                better_cost = graph.dist(current_node, i);
+                next_on_path = Some(i);
+            }
+        }
+
+        if let Some(next_on_path) = next_on_path {
+            let n = next_on_path;
+            path.push(n);
+            cost += better_cost;
+            current_node = n;
+            visited[n] = true;
+            visited_count += 1;
+        }
+    }
+
+    cost += graph.dist(current_node, start);
+
+    Solution { route: path, cost }
+}
+
+/// Implementação da Heurística de Inserção Mais Próxima (*Nearest Insertion*), um algoritmo
+/// guloso utilizado para gerar um ciclo aproximado para o Problema do Caixeiro Viajante (TSP).
+///
+/// # Argumentos
+/// - `graph`: um grafo representado por uma matriz de adjacência, onde `graph.dist(i, j)` indica
+///   o custo (ou distância) da aresta entre os vértices `i` e `j`;
+/// - `start`: o vértice inicial que será utilizado como base para a construção do ciclo.
+///
+/// # Saída
+/// - Um vetor de vértices que representa o ciclo encontrado, iniciando e terminando no vértice `start`.
+///
+/// # Detalhes de funcionamento
+/// 1. Cria-se um vetor de booleanos `in_cycle` para marcar se um vértice já pertence ao ciclo,
+///    permitindo consultas em tempo O(1).
+/// 2. Inicializa o ciclo com o vértice `start` e o seu vizinho mais próximo, formando um ciclo
+///    inicial mínimo do tipo `start → v → start`.
+/// 3. Cria-se um vetor auxiliar `min_dist`, que armazena, para cada vértice fora do ciclo,
+///    a menor distância até qualquer vértice já presente no ciclo.
+/// 4. Enquanto ainda houver vértices fora do ciclo, escolhe-se o vértice `r*` cuja distância
+///    ao ciclo é mínima (critério de *nearest*).
+/// 5. Para o vértice escolhido, testa-se todas as arestas consecutivas do ciclo a fim de encontrar
+///    a posição de inserção que minimize o custo adicional:
+///    custo_extra = d(u, r*) + d(r*, v) − d(u, v).
+/// 6. O vértice `r*` é então inserido na melhor posição do ciclo e marcado como pertencente ao ciclo.
+/// 7. O vetor `min_dist` é atualizado em tempo O(n), ajustando as distâncias mínimas dos vértices
+///    ainda não inseridos.
+/// 8. O processo continua até que todos os vértices estejam presentes no ciclo.
+pub fn nearest_insertion<G: DistanceMatrix + ?Sized>(graph: &G, start: usize) -> DynSolution {
+    insertion(graph, start, |min_dist, in_cycle| {
+        outside(in_cycle).min_by(|&u, &v| min_dist[u].total_cmp(&min_dist[v]))
+    })
+}
+
+/// Heurística de Inserção Mais Distante (*Farthest Insertion*).
+///
+/// Funciona como a [`nearest_insertion`], mas escolhe a cada passo o vértice cuja distância ao
+/// ciclo é **máxima**. Assim, o contorno geral do ciclo é definido pelos vértices mais
+/// afastados, e os vértices próximos ao ciclo são inseridos por último. Em instâncias métricas,
+/// costuma gerar ciclos bem melhores que a Inserção Mais Próxima.
+pub fn farthest_insertion<G: DistanceMatrix + ?Sized>(graph: &G, start: usize) -> DynSolution {
+    insertion(graph, start, |min_dist, in_cycle| {
+        outside(in_cycle).max_by(|&u, &v| min_dist[u].total_cmp(&min_dist[v]))
+    })
+}
+
+/// Heurística de Inserção Aleatória (*Random Insertion*).
+///
+/// Funciona como a [`nearest_insertion`], mas o vértice inserido a cada passo é sorteado com
+/// `rng` entre os vértices fora do ciclo.
+pub fn random_insertion<G: DistanceMatrix + ?Sized, R: Rng + ?Sized>(
+    graph: &G,
+    start: usize,
+    rng: &mut R,
+) -> DynSolution {
+    insertion(graph, start, |_, in_cycle| {
+        let candidates: Vec<usize> = outside(in_cycle).collect();
+        candidates.choose(rng).copied()
+    })
+}
+
+/// Vizinho Mais Próximo semiguloso, usado na construção do GRASP.
+///
+/// Funciona como o [`nearest_neighbour`], mas o próximo vértice é sorteado com `rng` da lista
+/// restrita de candidatos (RCL): os vértices não visitados cuja distância até o vértice atual é
+/// no máximo `min + alpha (max - min)`. Com `alpha = 0` a construção é gulosa, e com `alpha = 1`
+/// a rota é aleatória.
+pub fn randomized_nearest_neighbour<G: DistanceMatrix + ?Sized, R: Rng + ?Sized>(
+    graph: &G,
+    start: usize,
+    alpha: f64,
+    rng: &mut R,
+) -> DynSolution {
+    let mut visited = vec![false; graph.node_count()];
+    visited[start] = true;
+    let mut route = vec![start];
+    let mut current = start;
+
+    while let Some(next) =
+        restricted_choice(outside(&visited), |v| graph.dist(current, v), alpha, rng)
+    {
+        route.push(next);
+        visited[next] = true;
+        current = next;
+    }
+
+    let cost = DynSolution::calculate_cost(&route, graph);
+    Solution { route, cost }
+}
+
+/// Inserção Mais Próxima semigulosa, usada na construção do GRASP.
+///
+/// Funciona como a [`nearest_insertion`], mas o vértice inserido é sorteado da lista restrita de
+/// candidatos sobre a distância ao ciclo, como no [`randomized_nearest_neighbour`].
+pub fn randomized_nearest_insertion<G: DistanceMatrix + ?Sized, R: Rng + ?Sized>(
+    graph: &G,
+    start: usize,
+    alpha: f64,
+    rng: &mut R,
+) -> DynSolution {
+    insertion(graph, start, |min_dist, in_cycle| {
+        restricted_choice(outside(in_cycle), |v| min_dist[v], alpha, rng)
+    })
+}
+
+/// Heurística de Inserção Mais Barata (*Cheapest Insertion*).
+///
+/// A partir do ciclo formado por `start` e o seu vizinho mais próximo, insere a cada passo o
+/// vértice (e a posição) de menor custo extra `d(u, r) + d(r, v) - d(u, v)` entre todos os
+/// vértices fora do ciclo.
+pub fn cheapest_insertion<G: DistanceMatrix + ?Sized>(graph: &G, start: usize) -> DynSolution {
+    let n = graph.node_count();
+    let first = (0..n)
+        .filter(|&v| v != start)
+        .min_by(|&u, &v| graph.dist(u, start).total_cmp(&graph.dist(v, start)));
+    let cycle = match first {
+        Some(first) => vec![start, first],
+        None => vec![start],
+    };
+    cheapest_from(graph, cycle)
+}
+
+/// Heurística de Inserção pelo Fecho Convexo (*Convex Hull Insertion*).
+///
+/// O ciclo inicial é o fecho convexo das coordenadas `coords` das cidades, percorrido no sentido
+/// anti-horário; os demais vértices são inseridos como na [`cheapest_insertion`]. Só faz sentido
+/// quando os custos são derivados das coordenadas (e.g. distância euclidiana).
+pub fn convex_hull_insertion<G: DistanceMatrix + ?Sized>(
+    graph: &G,
+    coords: &[(f64, f64)],
+) -> DynSolution {
+    assert_eq!(
+        coords.len(),
+        graph.node_count(),
+        "expected one coordinate per node"
+    );
+    cheapest_from(graph, convex_hull(coords))
+}
+
+/// Vértices fora do ciclo.
+fn outside(in_cycle: &[bool]) -> impl Iterator<Item = usize> + '_ {
+    (0..in_cycle.len()).filter(|&v| !in_cycle[v])
+}
+
+/// Estrutura comum às heurísticas de inserção que escolhem primeiro o vértice e depois a sua
+/// posição no ciclo.
+///
+/// O ciclo começa apenas com `start`. A cada passo, `select` recebe `min_dist` (a menor
+/// distância de cada vértice até o ciclo) e `in_cycle`, e escolhe o próximo vértice, que é
+/// inserido na posição de menor custo extra. Em seguida, `min_dist` é atualizado em O(n).
+fn insertion<G: DistanceMatrix + ?Sized>(
+    graph: &G,
+    start: usize,
+    mut select: impl FnMut(&[f64], &[bool]) -> Option<usize>,
+) -> DynSolution {
+    let n = graph.node_count();
+    let mut in_cycle = vec![false; n];
+    let mut min_dist: Vec<f64> = (0..n).map(|v| graph.dist(v, start)).collect();
+    in_cycle[start] = true;
+    let mut cycle = vec![start];
+
+    while let Some(r_star) = select(&min_dist, &in_cycle) {
+        let best_pos = (0..cycle.len())
+            .map(|i| {
+                let (u, v) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+                let extra = graph.dist(u, r_star) + graph.dist(r_star, v) - graph.dist(u, v);
+                (i, extra)
+            })
+            .fold((0, f64::INFINITY), |best, (i, extra)| {
+                if extra < best.1 { (i, extra) } else { best }
+            })
+            .0;
+
+        cycle.insert(best_pos + 1, r_star);
+        in_cycle[r_star] = true;
+
+        for v in outside(&in_cycle) {
+            min_dist[v] = min_dist[v].min(graph.dist(v, r_star));
+        }
+    }
+
+    let cost = DynSolution::calculate_cost(&cycle, graph);
+    Solution { route: cycle, cost }
+}
+
+/// Sorteia um vértice da lista restrita de candidatos: os vértices de `candidates` cujo `value`
+/// é no máximo `min + alpha (max - min)`.
+fn restricted_choice<R: Rng + ?Sized>(
+    candidates: impl Iterator<Item = usize>,
+    value: impl Fn(usize) -> f64,
+    alpha: f64,
+    rng: &mut R,
+) -> Option<usize> {
+    let candidates: Vec<(usize, f64)> = candidates.map(|v| (v, value(v))).collect();
+    let (min, max) = candidates
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &(_, c)| {
+            (min.min(c), max.max(c))
+        });
+    // Com `alpha = 0`, custos infinitos não podem transformar o limiar em NaN.
+    let threshold = if alpha > 0. {
+        min + alpha * (max - min)
+    } else {
+        min
+    };
+    let restricted: Vec<usize> = candidates
+        .into_iter()
+        .filter(|&(_, c)| c <= threshold)
+        .map(|(v, _)| v)
+        .collect();
+    restricted.choose(rng).copied()
+}
+
+/// Inserção Mais Barata a partir do ciclo `cycle`.
+///
+/// Para cada vértice fora do ciclo, guarda o menor custo extra de inserção e a aresta `(u, v)`
+/// onde ele é obtido. Ao inserir `r` entre `u` e `v`, só os vértices cuja melhor aresta era
+/// `(u, v)` precisam ser recalculados; os demais só comparam o custo atual com o das arestas
+/// novas `(u, r)` e `(r, v)`.
+fn cheapest_from<G: DistanceMatrix + ?Sized>(graph: &G, mut cycle: Vec<usize>) -> DynSolution {
+    let n = graph.node_count();
+    let mut in_cycle = vec![false; n];
+    for &v in &cycle {
+        in_cycle[v] = true;
+    }
+
+    let extra = |u: usize, r: usize, v: usize| {
+        if u == v {
+            graph.dist(u, r) + graph.dist(r, v)
+        } else {
+            graph.dist(u, r) + graph.dist(r, v) - graph.dist(u, v)
+        }
+    };
+    let best_edge = |cycle: &[usize], r: usize| {
+        (0..cycle.len())
+            .map(|i| {
+                let (u, v) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+                (extra(u, r, v), (u, v))
+            })
+            .fold((f64::INFINITY, (cycle[0], cycle[0])), |best, e| {
+                if e.0 < best.0 { e } else { best }
+            })
+    };
+
+    let mut best: Vec<(f64, (usize, usize))> = (0..n)
+        .map(|r| {
+            if in_cycle[r] {
+                (f64::INFINITY, (r, r))
+            } else {
+                best_edge(&cycle, r)
+            }
+        })
+        .collect();
+
+    while let Some(r_star) = outside(&in_cycle).min_by(|&a, &b| best[a].0.total_cmp(&best[b].0)) {
+        let (u, v) = best[r_star].1;
+        let pos = cycle.iter().position(|&w| w == u).unwrap();
+        cycle.insert(pos + 1, r_star);
+        in_cycle[r_star] = true;
+
+        for r in (0..n).filter(|&r| !in_cycle[r]) {
+            if best[r].1 == (u, v) {
+                best[r] = best_edge(&cycle, r);
+            } else {
+                for edge in [(u, r_star), (r_star, v)] {
+                    let e = extra(edge.0, r, edge.1);
+                    if e < best[r].0 {
+                        best[r] = (e, edge);
+                    }
+                }
+            }
+        }
+    }
+
+    let cost = DynSolution::calculate_cost(&cycle, graph);
+    Solution { route: cycle, cost }
+}
+
+/// Fecho convexo dos pontos `coords` (algoritmo *monotone chain*), em sentido anti-horário.
+/// Retorna os índices dos pontos.
+fn convex_hull(coords: &[(f64, f64)]) -> Vec<usize> {
+    let mut points: Vec<usize> = (0..coords.len()).collect();
+    points.sort_by(|&a, &b| {
+        coords[a]
+            .0
+            .total_cmp(&coords[b].0)
+            .then(coords[a].1.total_cmp(&coords[b].1))
+    });
+    if points.len() < 3 {
+        return points;
+    }
+
+    let cross = |o: usize, a: usize, b: usize| {
+        let (o, a, b) = (coords[o], coords[a], coords[b]);
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let mut hull: Vec<usize> = Vec::with_capacity(points.len() + 1);
+    for &p in &points {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0. {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    let lower = hull.len() + 1;
+    for &p in points.iter().rev().skip(1) {
+        while hull.len() >= lower && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0. {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    hull.pop();
+    hull
+}
+
+/// Heurística gulosa de arestas (*Greedy Edge*).
+///
+/// Ordena todas as arestas por custo e aceita, da mais barata para a mais cara, cada aresta que
+/// não deixa um vértice com grau maior que 2 nem fecha um ciclo prematuro (verificado com
+/// *union-find*). As arestas aceitas formam um caminho hamiltoniano, que é fechado ligando as
+/// suas duas pontas. A rota retornada começa pelo vértice 0.
+pub fn greedy_edge<G: DistanceMatrix + ?Sized>(graph: &G) -> DynSolution {
+    let n = graph.node_count();
+    let mut edges: Vec<(usize, usize)> = (0..n)
+        .flat_map(|u| (u + 1..n).map(move |v| (u, v)))
+        .collect();
+    edges.sort_by(|&(a, b), &(c, d)| graph.dist(a, b).total_cmp(&graph.dist(c, d)));
+
+    let mut route = join_paths(n, None, edges);
+    if let Some(p) = route.iter().position(|&v| v == 0) {
+        route.rotate_left(p);
+    }
+    let cost = DynSolution::calculate_cost(&route, graph);
+    Solution { route, cost }
+}
+
+/// Heurística das economias de Clarke–Wright (*savings*), ao redor do vértice `hub`.
+///
+/// Começa com uma rota `hub → v → hub` para cada outro vértice `v` e, em ordem decrescente de
+/// economia `s(u, v) = d(hub, u) + d(hub, v) - d(u, v)`, junta as rotas cujas pontas são `u` e `v`
+/// em uma só. Ao fim, resta uma única rota, que começa por `hub`.
+pub fn clarke_wright<G: DistanceMatrix + ?Sized>(graph: &G, hub: usize) -> DynSolution {
+    let n = graph.node_count();
+    let saving = |u: usize, v: usize| graph.dist(hub, u) + graph.dist(hub, v) - graph.dist(u, v);
+    let mut edges: Vec<(usize, usize)> = (0..n)
+        .filter(|&u| u != hub)
+        .flat_map(|u| (u + 1..n).filter(|&v| v != hub).map(move |v| (u, v)))
+        .collect();
+    edges.sort_by(|&(a, b), &(c, d)| saving(c, d).total_cmp(&saving(a, b)));
+
+    let mut route = vec![hub];
+    route.extend(join_paths(n, Some(hub), edges));
+    let cost = DynSolution::calculate_cost(&route, graph);
+    Solution { route, cost }
+}
+
+/// Aceita, na ordem dada, as arestas de `edges` que mantêm todos os vértices com grau no máximo 2
+/// e não fecham ciclos, até formar um único caminho com todos os vértices exceto `excluded`.
+/// Retorna os vértices na ordem do caminho.
+fn join_paths(
+    n: usize,
+    excluded: Option<usize>,
+    edges: impl IntoIterator<Item = (usize, usize)>,
+) -> Vec<usize> {
+    let size = n - usize::from(excluded.is_some());
+    let mut sets = DisjointSets::new(n);
+    let mut adjacent: Vec<Vec<usize>> = vec![Vec::with_capacity(2); n];
+    let mut accepted = 0;
+
+    for (u, v) in edges {
+        if accepted + 1 >= size {
+            break;
+        }
+        if adjacent[u].len() < 2 && adjacent[v].len() < 2 && sets.union(u, v) {
+            adjacent[u].push(v);
+            adjacent[v].push(u);
+            accepted += 1;
+        }
+    }
+
+    // Percorre o caminho a partir de uma das pontas.
+    let Some(mut current) = (0..n).find(|&v| Some(v) != excluded && adjacent[v].len() < 2) else {
+        return Vec::new();
+    };
+    let mut path = vec![current];
+    let mut previous = usize::MAX;
+    while let Some(&next) = adjacent[current].iter().find(|&&v| v != previous) {
+        path.push(next);
+        (previous, current) = (current, next);
+    }
+    path
+}
+
+/// Conjuntos disjuntos (*union-find*) com compressão de caminhos e união por posto.
+struct DisjointSets {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+        }
+    }
+
+    fn find(&mut self, v: usize) -> usize {
+        if self.parent[v] != v {
+            self.parent[v] = self.find(self.parent[v]);
+        }
+        self.parent[v]
+    }
+
+    /// Une os conjuntos de `u` e `v`. Retorna `false` se eles já estavam no mesmo conjunto.
+    fn union(&mut self, u: usize, v: usize) -> bool {
+        let (u, v) = (self.find(u), self.find(v));
+        if u == v {
+            return false;
+        }
+        match self.rank[u].cmp(&self.rank[v]) {
+            Ordering::Less => self.parent[u] = v,
+            Ordering::Greater => self.parent[v] = u,
+            Ordering::Equal => {
+                self.parent[v] = u;
+                self.rank[u] += 1;
+            }
+        }
+        true
+    }
+}
+
+/// Vizinho Mais Próximo seguido de uma busca local swap a partir da posição `start`.
+pub fn nearest_neighbour_with_swap<G: DistanceMatrix + ?Sized>(
+    graph: &G,
+    start: usize,
+) -> DynSolution {
+    let first_solution = nearest_neighbour(graph, start);
+    first_solution.swap(graph, start)
+}
+
+/// Inserção Mais Próxima seguida de uma busca local or-opt.
+pub fn nearest_insertion_with_or_opt<G: DistanceMatrix + ?Sized>(
+    graph: &G,
+    start: usize,
+) -> DynSolution {
+    let first_solution = nearest_insertion(graph, start);
+    first_solution.or_opt(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::Matrix;
+    use rand::{SeedableRng, rngs::StdRng};
+    const INF: f64 = f64::INFINITY;
+
+    #[test]
+    fn nearest_neighbour_1() {
+        let graph = vec![
+            vec![INF, 1.0, 2.0, 4.0, 3.0],
+            vec![1.0, INF, 7.0, 2.0, 5.0],
+            vec![2.0, 7.0, INF, 8.0, 1.0],
+            vec![4.0, 2.0, 8.0, INF, 6.0],
+            vec![3.0, 5.0, 1.0, 6.0, INF],
+        ];
+
+        let solution = nearest_neighbour(&graph, 0);
+
+        assert_eq!(solution.cost, 12.0);
+        assert_eq!(solution.route[0], 0);
+        assert_eq!(solution.route.last(), Some(2).as_ref());
+    }
+
+    #[test]
+    fn nearest_neighbour_2() {
+        let graph = vec![
+            vec![INF, 1.0, 3.0, 6.0],
+            vec![1.0, INF, 2.0, 3.0],
+            vec![3.0, 2.0, INF, 1.0],
+            vec![6.0, 3.0, 1.0, INF],
+        ];
+
+        let solution = nearest_neighbour(&graph, 0);
+
+        assert_eq!(solution.cost, 10.0);
+        assert_eq!(solution.route[0], 0);
+        assert_eq!(solution.route.last(), Some(3).as_ref());
+    }
+
+    #[test]
+    fn test_3() {
+        let graph = vec![
+            vec![INF, 1.0, 3.0, 1000.0],
+            vec![1.0, INF, 2.0, 3.0],
+            vec![3.0, 2.0, INF, 1.0],
+            vec![1000.0, 3.0, 1.0, INF],
+        ];
+
+        let solution = nearest_neighbour(&graph, 0);
+
+        assert_eq!(solution.cost, 1004.0);
+        assert_eq!(solution.route[0], 0);
+        assert_eq!(solution.route.last(), Some(3).as_ref());
+    }
+
+    fn is_valid_cycle(route: &[usize], n: usize) -> bool {
+        if route.len() != n {
+            return false;
+        }
+
+        let mut visited = vec![false; n];
+        for &v in route.iter() {
+            if v >= n || visited[v] {
+                return false;
+            }
+            visited[v] = true;
+        }
+
+        true
+    }
+
+    #[test]
+    fn tsp_1() {
+        let graph = vec![
+            vec![INF, 10.0, 15.0, 20.0],
+            vec![10.0, INF, 35.0, 25.0],
+            vec![15.0, 35.0, INF, 30.0],
+            vec![20.0, 25.0, 30.0, INF],
+        ];
+
+        let solution = nearest_insertion(&graph, 0);
+
+        assert!(is_valid_cycle(&solution.route, graph.len()));
+        assert!(solution.cost > 0.0);
+    }
+
+    #[test]
+    fn tsp_2() {
+        let graph = vec![
+            vec![INF, 2.0, 9.0, 10.0, 7.0],
+            vec![2.0, INF, 6.0, 4.0, 3.0],
+            vec![9.0, 6.0, INF, 8.0, 5.0],
+            vec![10.0, 4.0, 8.0, INF, 6.0],
+            vec![7.0, 3.0, 5.0, 6.0, INF],
+        ];
+
+        let solution = nearest_insertion(&graph, 0);
+
+        assert!(is_valid_cycle(&solution.route, graph.len()));
+        assert!(solution.cost > 0.0);
+    }
+
+    #[test]
+    fn tsp_3() {
+        let graph = vec![
+            vec![INF, 1.0, 50.0, 100.0, 1.0],
+            vec![1.0, INF, 1.0, 50.0, 100.0],
+            vec![50.0, 1.0, INF, 1.0, 50.0],
+            vec![100.0, 50.0, 1.0, INF, 1.0],
+            vec![1.0, 100.0, 50.0, 1.0, INF],
+        ];
+
+        let solution = nearest_insertion(&graph, 0);
+
+        assert!(is_valid_cycle(&solution.route, graph.len()));
+        assert!(solution.cost > 0.0);
+    }
+
+    fn ring() -> Vec<Vec<f64>> {
+        vec![
+            vec![INF, 1.0, 50.0, 100.0, 1.0],
+            vec![1.0, INF, 1.0, 50.0, 100.0],
+            vec![50.0, 1.0, INF, 1.0, 50.0],
+            vec![100.0, 50.0, 1.0, INF, 1.0],
+            vec![1.0, 100.0, 50.0, 1.0, INF],
+        ]
+    }
+
+    #[test]
+    fn farthest_insertion_test() {
+        let graph = ring();
+
+        let solution = farthest_insertion(&graph, 0);
+
+        assert!(is_valid_cycle(&solution.route, graph.len()));
+        assert_eq!(solution.route[0], 0);
+        assert_eq!(solution.cost, 5.0);
+    }
+
+    #[test]
+    fn cheapest_insertion_test() {
+        let graph = ring();
+
+        let solution = cheapest_insertion(&graph, 2);
+
+        assert!(is_valid_cycle(&solution.route, graph.len()));
+        assert_eq!(solution.route[0], 2);
+        assert_eq!(solution.cost, 5.0);
+    }
+
+    #[test]
+    fn random_insertion_test() {
+        let graph = vec![
+            vec![INF, 2.0, 9.0, 10.0, 7.0],
+            vec![2.0, INF, 6.0, 4.0, 3.0],
+            vec![9.0, 6.0, INF, 8.0, 5.0],
+            vec![10.0, 4.0, 8.0, INF, 6.0],
+            vec![7.0, 3.0, 5.0, 6.0, INF],
+        ];
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for start in 0..graph.len() {
+            let solution = random_insertion(&graph, start, &mut rng);
+
+            assert!(is_valid_cycle(&solution.route, graph.len()));
+            assert_eq!(solution.route[0], start);
+            assert_eq!(
+                solution.cost,
+                DynSolution::calculate_cost(&solution.route, &graph)
+            );
+        }
+    }
+
+    #[test]
+    fn randomized_construction_test() {
+        let graph = vec![
+            vec![INF, 2.0, 9.0, 10.0, 7.0],
+            vec![2.0, INF, 6.0, 4.0, 3.0],
+            vec![9.0, 6.0, INF, 8.0, 5.0],
+            vec![10.0, 4.0, 8.0, INF, 6.0],
+            vec![7.0, 3.0, 5.0, 6.0, INF],
+        ];
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for start in 0..graph.len() {
+            // Sem empates, `alpha = 0` reproduz as construções gulosas.
+            let s = randomized_nearest_neighbour(&graph, start, 0.0, &mut rng);
+            assert_eq!(s.route, nearest_neighbour(&graph, start).route);
+            let s = randomized_nearest_insertion(&graph, start, 0.0, &mut rng);
+            assert_eq!(s.route, nearest_insertion(&graph, start).route);
+
+            for alpha in [0.5, 1.0] {
+                for s in [
+                    randomized_nearest_neighbour(&graph, start, alpha, &mut rng),
+                    randomized_nearest_insertion(&graph, start, alpha, &mut rng),
+                ] {
+                    assert!(is_valid_cycle(&s.route, graph.len()));
+                    assert_eq!(s.route[0], start);
+                    assert_eq!(s.cost, DynSolution::calculate_cost(&s.route, &graph));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn convex_hull_insertion_test() {
+        // Quadrado com um ponto no centro e um ponto sobre uma aresta.
+        let coords = [
+            (0.0, 0.0),
+            (2.0, 1.0),
+            (4.0, 0.0),
+            (4.0, 4.0),
+            (0.0, 4.0),
+            (2.0, 0.0),
+        ];
+        assert_eq!(convex_hull(&coords), [0, 2, 3, 4]);
+
+        let graph = Matrix::from_fn(coords.len(), |u, v| {
+            let (dx, dy) = (coords[u].0 - coords[v].0, coords[u].1 - coords[v].1);
+            (dx * dx + dy * dy).sqrt()
+        });
+
+        let solution = convex_hull_insertion(&graph, &coords);
+
+        assert!(is_valid_cycle(&solution.route, coords.len()));
+        assert!((solution.cost - (15.0 + 5f64.sqrt())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn greedy_edge_test() {
+        let graph = ring();
+
+        let solution = greedy_edge(&graph);
+
+        assert!(is_valid_cycle(&solution.route, graph.len()));
+        assert_eq!(solution.route[0], 0);
+        assert_eq!(solution.cost, 5.0);
+
+        let graph = vec![
+            vec![INF, 10.0, 15.0, 20.0],
+            vec![10.0, INF, 35.0, 25.0],
+            vec![15.0, 35.0, INF, 30.0],
+            vec![20.0, 25.0, 30.0, INF],
+        ];
+
+        // Aceita (0, 1), (0, 2) e (1, 3); a aresta (2, 3) fecha o ciclo.
+        let solution = greedy_edge(&graph);
+        assert_eq!(solution.route, [0, 1, 3, 2]);
+        assert_eq!(solution.cost, 80.0);
+    }
+
+    #[test]
+    fn clarke_wright_test() {
+        let graph = ring();
+
+        for hub in 0..graph.len() {
+            let solution = clarke_wright(&graph, hub);
+
+            assert!(is_valid_cycle(&solution.route, graph.len()));
+            assert_eq!(solution.route[0], hub);
+            assert_eq!(solution.cost, 5.0);
+        }
+
+        let graph = vec![
+            vec![INF, 2.0, 9.0, 10.0, 7.0],
+            vec![2.0, INF, 6.0, 4.0, 3.0],
+            vec![9.0, 6.0, INF, 8.0, 5.0],
+            vec![10.0, 4.0, 8.0, INF, 6.0],
+            vec![7.0, 3.0, 5.0, 6.0, INF],
+        ];
+
+        let solution = clarke_wright(&graph, 0);
+        assert!(is_valid_cycle(&solution.route, graph.len()));
+    }
+}
@@ -0,0 +1,501 @@
+//! Algoritmos exatos para o Caixeiro Viajante.
+//!
+//! A programação dinâmica de Held–Karp calcula, para cada subconjunto `S` de vértices e cada
+//! vértice `j` de `S`, o menor custo de um caminho que sai do vértice 0, visita exatamente os
+//! vértices de `S` e termina em `j`. Ela custa O(n² · 2ⁿ) em tempo e O(n · 2ⁿ) em memória, então
+//! só é viável para instâncias pequenas; acima do limite configurado, o solver se recusa a
+//! executar com um [`TooLarge`]. Como não assume simetria nem desigualdade triangular, também
+//! serve de oráculo nos testes das heurísticas.
+//!
+//! Para instâncias médias, o [`BranchAndBound`] faz uma busca em profundidade sobre as rotas
+//! parciais que começam pelo vértice 0. O limitante superior inicial vem da Inserção Mais Próxima
+//! com or-opt, e cada rota parcial é podada quando o custo do caminho mais um limitante inferior
+//! para completá-lo não melhora a melhor rota conhecida. O limitante para completar o caminho é o
+//! da 1-árvore: uma árvore geradora dos vértices restantes mais as arestas mais baratas que a
+//! ligam ao último vértice do caminho e ao vértice 0, com as penalidades da subida de
+//! subgradiente de [`one_tree::subgradient_bound`]. Com tempo limite, a busca pode terminar sem
+//! provar a otimalidade; nesse caso, o limitante reportado é o de Held–Karp.
+
+use std::time::{Duration, Instant};
+use std::{error, fmt};
+
+use crate::construction;
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{DynSolution, Solution};
+use crate::mst;
+use crate::one_tree::{self, Subgradient};
+
+/// Melhora mínima para que uma rota substitua a melhor conhecida.
+const EPSILON: f64 = 1e-9;
+
+/// Erro retornado quando a instância tem mais vértices que o limite do solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooLarge {
+    pub node_count: usize,
+    pub limit: usize,
+}
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "instance has {} nodes, but the exact solver is limited to {}",
+            self.node_count, self.limit
+        )
+    }
+}
+
+impl error::Error for TooLarge {}
+
+/// Solver exato por programação dinâmica (Held–Karp).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeldKarp {
+    /// Quantidade máxima de vértices aceita, limitada a [`HeldKarp::MAX_NODES`]. Com 20 vértices,
+    /// a tabela ocupa cerca de 80 MB.
+    pub max_nodes: usize,
+}
+
+impl Default for HeldKarp {
+    fn default() -> Self {
+        Self { max_nodes: 20 }
+    }
+}
+
+impl HeldKarp {
+    /// Limite absoluto de vértices, qualquer que seja `max_nodes`: com 25 vértices, a tabela já
+    /// ocupa alguns GB.
+    pub const MAX_NODES: usize = 25;
+
+    /// Calcula uma rota ótima de `graph`, começando pelo vértice 0.
+    pub fn solve<G: DistanceMatrix + ?Sized>(&self, graph: &G) -> Result<DynSolution, TooLarge> {
+        let n = graph.node_count();
+        let limit = self.max_nodes.min(Self::MAX_NODES);
+        if n > limit {
+            return Err(TooLarge {
+                node_count: n,
+                limit,
+            });
+        }
+        if n <= 1 {
+            return Ok(Solution {
+                route: (0..n).collect(),
+                cost: 0.,
+            });
+        }
+
+        // Os vértices `1..n` são os bits `0..m` dos subconjuntos; com o limite de vértices, os
+        // predecessores cabem em um `u8`.
+        let m = n - 1;
+        debug_assert!(m <= u8::MAX as usize);
+        let full = (1usize << m) - 1;
+        let mut cost = vec![f64::INFINITY; (full + 1) * m];
+        let mut previous = vec![0u8; (full + 1) * m];
+        for j in 0..m {
+            cost[(1 << j) * m + j] = graph.dist(0, j + 1);
+        }
+
+        for mask in 1..=full {
+            for j in (0..m).filter(|&j| mask & (1 << j) != 0) {
+                let rest = mask & !(1 << j);
+                if rest == 0 {
+                    continue;
+                }
+                for i in (0..m).filter(|&i| rest & (1 << i) != 0) {
+                    let c = cost[rest * m + i] + graph.dist(i + 1, j + 1);
+                    if c < cost[mask * m + j] {
+                        cost[mask * m + j] = c;
+                        previous[mask * m + j] = i as u8;
+                    }
+                }
+            }
+        }
+
+        let (mut last, best) = (0..m)
+            .map(|j| (j, cost[full * m + j] + graph.dist(j + 1, 0)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+
+        let mut route = Vec::with_capacity(n);
+        let mut mask = full;
+        while mask != 0 {
+            route.push(last + 1);
+            let next = previous[mask * m + last] as usize;
+            mask &= !(1 << last);
+            last = next;
+        }
+        route.push(0);
+        route.reverse();
+
+        Ok(Solution { route, cost: best })
+    }
+}
+
+/// Rota ótima de `graph` pelo [`HeldKarp`] com o limite padrão de vértices.
+pub fn held_karp<G: DistanceMatrix + ?Sized>(graph: &G) -> Result<DynSolution, TooLarge> {
+    HeldKarp::default().solve(graph)
+}
+
+/// Busca em profundidade com poda por limitantes (*branch and bound*).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BranchAndBound {
+    /// Tempo máximo de busca; `None` executa até provar a otimalidade.
+    pub time_limit: Option<Duration>,
+}
+
+/// Resultado do [`BranchAndBound`].
+#[derive(Clone)]
+pub struct BranchAndBoundResult {
+    /// Melhor rota encontrada, começando pelo vértice 0.
+    pub solution: DynSolution,
+    /// Limitante inferior para o custo ótimo: o custo da rota, se ela for ótima, ou o limitante
+    /// de Held–Karp, caso contrário.
+    pub lower_bound: f64,
+    /// Quantidade de nós explorados na árvore de busca.
+    pub nodes: usize,
+    /// Se a busca terminou antes do tempo limite, provando que a rota é ótima.
+    pub optimal: bool,
+}
+
+impl BranchAndBoundResult {
+    /// Distância relativa do custo da rota até o limitante inferior.
+    pub fn gap(&self) -> f64 {
+        one_tree::gap(self.solution.cost, self.lower_bound)
+    }
+}
+
+impl BranchAndBound {
+    /// Procura uma rota ótima de `graph` até provar a otimalidade ou esgotar o tempo limite.
+    pub fn solve<G: DistanceMatrix>(&self, graph: &G) -> BranchAndBoundResult {
+        let start = Instant::now();
+        let n = graph.node_count();
+        if n <= 1 {
+            return BranchAndBoundResult {
+                solution: Solution {
+                    route: (0..n).collect(),
+                    cost: 0.,
+                },
+                lower_bound: 0.,
+                nodes: 0,
+                optimal: true,
+            };
+        }
+
+        let best = construction::nearest_insertion_with_or_opt(graph, 0);
+        let bound = one_tree::subgradient_bound(
+            graph,
+            &Subgradient {
+                upper_bound: Some(best.cost),
+                ..Subgradient::default()
+            },
+        );
+
+        let mut visited = vec![false; n];
+        visited[0] = true;
+        let mut search = Search {
+            graph,
+            penalties: bound.penalties,
+            deadline: self.time_limit.map(|limit| start + limit),
+            route: vec![0],
+            visited,
+            best,
+            nodes: 0,
+            timed_out: false,
+        };
+        search.visit(0.);
+
+        let optimal = !search.timed_out;
+        BranchAndBoundResult {
+            lower_bound: if optimal {
+                search.best.cost
+            } else {
+                bound.value
+            },
+            solution: search.best,
+            nodes: search.nodes,
+            optimal,
+        }
+    }
+}
+
+/// Rota de `graph` pelo [`BranchAndBound`], sem tempo limite.
+pub fn branch_and_bound<G: DistanceMatrix>(graph: &G) -> BranchAndBoundResult {
+    BranchAndBound::default().solve(graph)
+}
+
+/// Estado da busca em profundidade: o caminho atual e a melhor rota conhecida.
+struct Search<'a, G> {
+    graph: &'a G,
+    penalties: Vec<f64>,
+    deadline: Option<Instant>,
+    route: Vec<usize>,
+    visited: Vec<bool>,
+    best: DynSolution,
+    nodes: usize,
+    timed_out: bool,
+}
+
+/// Os vértices `nodes` de `graph`, renumerados a partir de 0 e com as penalidades aplicadas.
+struct Remaining<'a, G> {
+    graph: &'a G,
+    penalties: &'a [f64],
+    nodes: &'a [usize],
+}
+
+impl<G: DistanceMatrix> DistanceMatrix for Remaining<'_, G> {
+    fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn dist(&self, u: usize, v: usize) -> f64 {
+        let (u, v) = (self.nodes[u], self.nodes[v]);
+        self.graph.dist(u, v).min(self.graph.dist(v, u)) + self.penalties[u] + self.penalties[v]
+    }
+}
+
+impl<G: DistanceMatrix> Search<'_, G> {
+    /// Limitante inferior para o custo de um caminho que sai de `last`, visita todos os vértices
+    /// ainda não visitados e termina no vértice 0.
+    ///
+    /// Com as penalidades, o caminho custa `Σ π` a mais em cada extremidade e `2 Σ π` a mais nos
+    /// vértices internos, que são descontados do peso da 1-árvore.
+    fn bound(&self, last: usize, remaining: &[usize]) -> f64 {
+        let (graph, pi) = (self.graph, &self.penalties);
+        let tree = mst::prim(&Remaining {
+            graph,
+            penalties: pi,
+            nodes: remaining,
+        });
+        let min = |f: &dyn Fn(usize) -> f64| {
+            remaining
+                .iter()
+                .map(|&v| f(v) + pi[v])
+                .fold(f64::INFINITY, f64::min)
+        };
+        let leave = min(&|v| graph.dist(last, v));
+        let enter = min(&|v| graph.dist(v, 0));
+        let internal: f64 = remaining.iter().map(|&v| pi[v]).sum();
+
+        tree.weight + leave + enter - 2. * internal
+    }
+
+    /// Explora as rotas que começam pelo caminho atual, que custa `cost`.
+    fn visit(&mut self, cost: f64) {
+        self.nodes += 1;
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.timed_out = true;
+            return;
+        }
+
+        let graph = self.graph;
+        let last = *self.route.last().unwrap();
+        let mut remaining: Vec<usize> = (0..self.visited.len())
+            .filter(|&v| !self.visited[v])
+            .collect();
+        if remaining.is_empty() {
+            let cost = cost + graph.dist(last, 0);
+            if cost < self.best.cost - EPSILON {
+                self.best = Solution {
+                    cost: DynSolution::calculate_cost(&self.route, graph),
+                    route: self.route.clone(),
+                };
+            }
+            return;
+        }
+        if cost + self.bound(last, &remaining) >= self.best.cost - EPSILON {
+            return;
+        }
+
+        // Os vizinhos mais próximos primeiro, para encontrar rotas boas mais cedo.
+        remaining.sort_by(|&u, &v| graph.dist(last, u).total_cmp(&graph.dist(last, v)));
+        for v in remaining {
+            self.route.push(v);
+            self.visited[v] = true;
+            self.visit(cost + graph.dist(last, v));
+            self.visited[v] = false;
+            self.route.pop();
+            if self.timed_out {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::Matrix;
+    use crate::instance;
+    use crate::local_search::LocalSearch;
+    use crate::test_util::is_permutation;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    /// Menor custo entre todas as permutações que começam pelo vértice 0.
+    fn brute_force(graph: &Matrix) -> f64 {
+        fn visit(graph: &Matrix, route: &mut Vec<usize>, left: &mut Vec<usize>, best: &mut f64) {
+            if left.is_empty() {
+                *best = best.min(DynSolution::calculate_cost(route, graph));
+            }
+            for i in 0..left.len() {
+                let v = left.swap_remove(i);
+                route.push(v);
+                visit(graph, route, left, best);
+                route.pop();
+                left.push(v);
+                let last = left.len() - 1;
+                left.swap(i, last);
+            }
+        }
+
+        let mut best = f64::INFINITY;
+        let mut left: Vec<usize> = (1..graph.node_count()).collect();
+        visit(graph, &mut vec![0], &mut left, &mut best);
+        best
+    }
+
+    #[test]
+    fn held_karp_test_1() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for n in 2..=8 {
+            // Custos assimétricos e sem desigualdade triangular.
+            let graph = Matrix::from_fn(n, |u, v| {
+                if u == v {
+                    0.
+                } else {
+                    rng.random_range(1..100) as f64
+                }
+            });
+
+            let solution = held_karp(&graph).unwrap();
+
+            assert!(is_permutation(&solution.route));
+            assert_eq!(solution.route[0], 0);
+            assert_eq!(
+                solution.cost,
+                DynSolution::calculate_cost(&solution.route, &graph)
+            );
+            assert_eq!(solution.cost, brute_force(&graph));
+        }
+    }
+
+    #[test]
+    fn held_karp_test_2() {
+        let graph = Matrix::filled(8, 1.);
+
+        let Err(error) = HeldKarp { max_nodes: 7 }.solve(&graph) else {
+            panic!("expected the instance to be rejected");
+        };
+        assert_eq!(
+            error,
+            TooLarge {
+                node_count: 8,
+                limit: 7
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "instance has 8 nodes, but the exact solver is limited to 7"
+        );
+        assert_eq!(HeldKarp { max_nodes: 8 }.solve(&graph).unwrap().cost, 8.);
+    }
+
+    #[test]
+    fn held_karp_test_3() {
+        // Um limite acima do absoluto não pode levar a tabelas impossíveis de alocar.
+        let graph = Matrix::filled(100, 1.);
+
+        let Err(error) = HeldKarp { max_nodes: 100 }.solve(&graph) else {
+            panic!("expected the instance to be rejected");
+        };
+        assert_eq!(
+            error,
+            TooLarge {
+                node_count: 100,
+                limit: HeldKarp::MAX_NODES
+            }
+        );
+        let Err(error) = HeldKarp {
+            max_nodes: usize::MAX,
+        }
+        .solve(&Matrix::filled(HeldKarp::MAX_NODES + 1, 1.)) else {
+            panic!("expected the instance to be rejected");
+        };
+        assert_eq!(error.limit, HeldKarp::MAX_NODES);
+    }
+
+    #[test]
+    fn oracle_test() {
+        // As buscas locais nunca podem passar do ótimo nas instâncias pequenas de `data/`.
+        for path in ["data/009", "data/010", "data/011", "data/012"] {
+            let graph = instance::load(format!("{path}/data.csv")).unwrap().matrix;
+            let optimum = held_karp(&graph).unwrap();
+
+            let start = DynSolution {
+                route: (0..graph.node_count()).collect(),
+                cost: 0.,
+            };
+            for s in [
+                start.two_opt(&graph),
+                start.or_opt(&graph),
+                start.lin_kernighan(&graph),
+            ] {
+                assert!(DynSolution::calculate_cost(&s.route, &graph) >= optimum.cost - 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn branch_and_bound_test_1() {
+        let mut rng = StdRng::seed_from_u64(23);
+        for n in 2..=10 {
+            let graph = Matrix::from_fn(n, |u, v| {
+                if u == v {
+                    0.
+                } else {
+                    rng.random_range(1..100) as f64
+                }
+            });
+
+            let result = branch_and_bound(&graph);
+
+            assert!(result.optimal);
+            assert!(result.nodes >= 1);
+            assert!(is_permutation(&result.solution.route));
+            assert_eq!(result.solution.route[0], 0);
+            assert_eq!(result.solution.cost, held_karp(&graph).unwrap().cost);
+            assert_eq!(result.lower_bound, result.solution.cost);
+            assert_eq!(result.gap(), 0.);
+        }
+    }
+
+    #[test]
+    fn branch_and_bound_test_2() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let points: Vec<(f64, f64)> = (0..30)
+            .map(|_| (rng.random_range(0.0..100.0), rng.random_range(0.0..100.0)))
+            .collect();
+        let graph = Matrix::from_fn(30, |u, v| {
+            let (dx, dy) = (points[u].0 - points[v].0, points[u].1 - points[v].1);
+            (dx * dx + dy * dy).sqrt()
+        });
+
+        // Sem tempo, a busca retorna a rota inicial e o limitante de Held–Karp.
+        let result = BranchAndBound {
+            time_limit: Some(Duration::ZERO),
+        }
+        .solve(&graph);
+
+        assert!(!result.optimal);
+        assert_eq!(result.nodes, 1);
+        assert!(is_permutation(&result.solution.route));
+        assert_eq!(
+            result.solution.cost,
+            construction::nearest_insertion_with_or_opt(&graph, 0).cost
+        );
+        assert!(result.lower_bound > mst::lower_bound(&graph));
+        assert!(result.lower_bound <= result.solution.cost);
+        assert!(result.gap() > 0.);
+    }
+}
@@ -0,0 +1,131 @@
+//! Representações de grafos completos por matriz de distâncias.
+//!
+//! As instâncias do Caixeiro Viajante podem ser conhecidas em tempo de compilação (geradas pela
+//! macro `graph_from_csv!` como `[[f64; N]; N]`) ou carregadas em tempo de execução (ver
+//! [`crate::instance`]), caso em que são guardadas em uma [`Matrix`]. O traço [`DistanceMatrix`]
+//! abstrai as duas formas para que heurísticas e buscas locais funcionem com qualquer uma.
+
+use std::ops::{Index, IndexMut};
+
+pub type Graph = Vec<Vec<f64>>;
+
+/// Grafo completo representado por uma matriz de distâncias (ou custos) entre os vértices.
+pub trait DistanceMatrix {
+    /// Quantidade de vértices do grafo.
+    fn node_count(&self) -> usize;
+
+    /// Custo da aresta que sai de `u` e chega em `v`.
+    fn dist(&self, u: usize, v: usize) -> f64;
+}
+
+impl<const N: usize> DistanceMatrix for [[f64; N]; N] {
+    #[inline]
+    fn node_count(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn dist(&self, u: usize, v: usize) -> f64 {
+        self[u][v]
+    }
+}
+
+impl DistanceMatrix for Graph {
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn dist(&self, u: usize, v: usize) -> f64 {
+        self[u][v]
+    }
+}
+
+/// Matriz de distâncias quadrada com tamanho definido em tempo de execução.
+///
+/// Os custos são guardados de forma contígua, linha a linha, e podem ser acessados tanto por
+/// [`DistanceMatrix::dist`] quanto por indexação dupla (`m[u][v]`), como nas matrizes geradas pela
+/// macro `graph_from_csv!`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    n: usize,
+    data: Vec<f64>,
+}
+
+impl Matrix {
+    /// Cria uma matriz `n × n` com todos os custos iguais a `value`.
+    pub fn filled(n: usize, value: f64) -> Self {
+        Self {
+            n,
+            data: vec![value; n * n],
+        }
+    }
+
+    /// Cria uma matriz a partir de suas linhas.
+    ///
+    /// # Panics
+    /// Caso alguma linha não possua o mesmo tamanho que a quantidade de linhas.
+    pub fn from_rows(rows: Vec<Vec<f64>>) -> Self {
+        let n = rows.len();
+        let mut data = Vec::with_capacity(n * n);
+        for row in rows {
+            assert_eq!(row.len(), n, "distance matrix must be square");
+            data.extend(row);
+        }
+        Self { n, data }
+    }
+
+    /// Cria uma matriz `n × n` onde o custo de `u` para `v` é dado por `f(u, v)`.
+    pub fn from_fn(n: usize, mut f: impl FnMut(usize, usize) -> f64) -> Self {
+        let mut data = Vec::with_capacity(n * n);
+        for u in 0..n {
+            for v in 0..n {
+                data.push(f(u, v));
+            }
+        }
+        Self { n, data }
+    }
+
+    /// Quantidade de vértices (linhas) da matriz.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Iterador sobre as linhas da matriz.
+    pub fn rows(&self) -> impl Iterator<Item = &[f64]> {
+        self.data.chunks_exact(self.n.max(1))
+    }
+}
+
+impl Index<usize> for Matrix {
+    type Output = [f64];
+
+    #[inline]
+    fn index(&self, u: usize) -> &[f64] {
+        &self.data[u * self.n..(u + 1) * self.n]
+    }
+}
+
+impl IndexMut<usize> for Matrix {
+    #[inline]
+    fn index_mut(&mut self, u: usize) -> &mut [f64] {
+        &mut self.data[u * self.n..(u + 1) * self.n]
+    }
+}
+
+impl DistanceMatrix for Matrix {
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn dist(&self, u: usize, v: usize) -> f64 {
+        self.data[u * self.n + v]
+    }
+}
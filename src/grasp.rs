@@ -0,0 +1,460 @@
+//! GRASP (*Greedy Randomized Adaptive Search Procedure*).
+//!
+//! Cada iteração constrói uma rota com uma heurística semigulosa, que sorteia o próximo vértice
+//! de uma lista restrita de candidatos controlada por `α` (veja
+//! [`construction::randomized_nearest_neighbour`]), e a refina com uma busca local de
+//! [`LocalSearch`](crate::local_search::LocalSearch). A melhor rota entre todas as iterações é o
+//! resultado.
+//!
+//! Duas extensões são opcionais:
+//! - no GRASP reativo, `α` é sorteado a cada iteração de um conjunto de valores, com
+//!   probabilidades que favorecem os valores cujas rotas tiveram, em média, os menores custos;
+//! - no *path relinking*, as melhores rotas distintas formam um conjunto elite, e cada rota
+//!   refinada percorre o caminho de trocas até uma rota elite sorteada. A melhor rota
+//!   intermediária desse caminho também é refinada pela busca local.
+
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use rand::seq::IndexedRandom;
+
+use crate::construction;
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{DynSolution, Move, Neighbourhood, Solution};
+
+/// Melhora mínima para que uma rota seja considerada melhor que outra.
+const EPSILON: f64 = 1e-9;
+
+/// Expoente que amplifica as diferenças entre as qualidades dos valores de `α` no GRASP reativo.
+const AMPLIFICATION: i32 = 10;
+
+/// Construção semigulosa usada a cada iteração.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Greedy {
+    /// [`construction::randomized_nearest_neighbour`].
+    NearestNeighbour,
+    /// [`construction::randomized_nearest_insertion`].
+    #[default]
+    NearestInsertion,
+}
+
+impl Greedy {
+    /// Constrói uma rota a partir de `start` com a lista restrita de candidatos de `alpha`.
+    pub fn construct<G: DistanceMatrix + ?Sized, R: Rng + ?Sized>(
+        self,
+        graph: &G,
+        start: usize,
+        alpha: f64,
+        rng: &mut R,
+    ) -> DynSolution {
+        match self {
+            Self::NearestNeighbour => {
+                construction::randomized_nearest_neighbour(graph, start, alpha, rng)
+            }
+            Self::NearestInsertion => {
+                construction::randomized_nearest_insertion(graph, start, alpha, rng)
+            }
+        }
+    }
+}
+
+impl FromStr for Greedy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nn" => Ok(Self::NearestNeighbour),
+            "ni" => Ok(Self::NearestInsertion),
+            _ => Err(format!("unknown construction `{s}`, expected `nn` or `ni`")),
+        }
+    }
+}
+
+/// Como `α` é escolhido a cada iteração.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Alpha {
+    /// O mesmo valor em todas as iterações.
+    Fixed(f64),
+    /// GRASP reativo: `α` é sorteado de `values`, e as probabilidades são recalculadas a cada
+    /// `period` iterações, proporcionais a `(melhor custo / custo médio do valor)^10`. `values`
+    /// não pode ser vazio, e `period` deve ser positivo.
+    Reactive { values: Vec<f64>, period: usize },
+}
+
+impl Alpha {
+    /// GRASP reativo com `α` entre 0,0 e 1,0, em passos de 0,1, recalculando as probabilidades a
+    /// cada 20 iterações.
+    pub fn reactive() -> Self {
+        Self::Reactive {
+            values: (0..=10).map(|i| i as f64 / 10.).collect(),
+            period: 20,
+        }
+    }
+}
+
+/// Configuração do GRASP. A busca termina ao atingir `max_iterations` ou `time_limit`, o que
+/// ocorrer primeiro; ao menos um dos dois deve ser informado.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grasp {
+    pub construction: Greedy,
+    pub alpha: Alpha,
+    /// Busca local aplicada após cada construção. As vizinhanças swap e shift partem de uma
+    /// posição sorteada.
+    pub neighbourhood: Neighbourhood,
+    /// Quantidade máxima de iterações.
+    pub max_iterations: Option<usize>,
+    /// Tempo máximo de execução.
+    pub time_limit: Option<Duration>,
+    /// Tamanho do conjunto elite do *path relinking*; `None` desativa o *path relinking*.
+    pub path_relinking: Option<usize>,
+}
+
+impl Default for Grasp {
+    fn default() -> Self {
+        Self {
+            construction: Greedy::default(),
+            alpha: Alpha::Fixed(0.2),
+            neighbourhood: Neighbourhood::TwoOpt,
+            max_iterations: Some(100),
+            time_limit: None,
+            path_relinking: None,
+        }
+    }
+}
+
+/// Resultado do GRASP.
+#[derive(Clone)]
+pub struct GraspResult {
+    /// Melhor rota encontrada.
+    pub solution: DynSolution,
+    /// Quantidade de iterações executadas.
+    pub iterations: usize,
+    /// Probabilidade final de cada valor de `α`, no GRASP reativo; vazio com `α` fixo.
+    pub probabilities: Vec<f64>,
+}
+
+/// Estatísticas do GRASP reativo.
+struct Reactive<'a> {
+    values: &'a [f64],
+    period: usize,
+    probabilities: Vec<f64>,
+    /// Soma dos custos e quantidade de rotas construídas com cada valor.
+    sums: Vec<f64>,
+    counts: Vec<usize>,
+}
+
+impl Reactive<'_> {
+    /// Sorteia o índice de um valor de `α` segundo as probabilidades atuais.
+    fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let mut r = rng.random_range(0.0..1.0);
+        for (i, &p) in self.probabilities.iter().enumerate() {
+            if r < p {
+                return i;
+            }
+            r -= p;
+        }
+        self.probabilities.len() - 1
+    }
+
+    /// Registra o custo de uma rota construída com o valor `i`.
+    fn record(&mut self, i: usize, cost: f64) {
+        self.sums[i] += cost;
+        self.counts[i] += 1;
+    }
+
+    /// Recalcula as probabilidades. Valores ainda não usados recebem a maior qualidade, para que
+    /// continuem sendo sorteados.
+    fn update(&mut self, best: f64) {
+        let quality: Vec<f64> = (0..self.values.len())
+            .map(|i| match self.counts[i] {
+                0 => 1.,
+                count => (best / (self.sums[i] / count as f64)).powi(AMPLIFICATION),
+            })
+            .collect();
+        let total: f64 = quality.iter().sum();
+        for (p, q) in self.probabilities.iter_mut().zip(quality) {
+            *p = q / total;
+        }
+    }
+}
+
+impl Grasp {
+    /// Executa o GRASP sobre `graph`.
+    pub fn run<G: DistanceMatrix, R: Rng + ?Sized>(&self, graph: &G, rng: &mut R) -> GraspResult {
+        assert!(
+            self.max_iterations.is_some() || self.time_limit.is_some(),
+            "GRASP needs an iteration or time budget"
+        );
+        let now = Instant::now();
+        let n = graph.node_count();
+
+        let (fixed, mut reactive) = match &self.alpha {
+            Alpha::Fixed(alpha) => (*alpha, None),
+            Alpha::Reactive { values, period } => {
+                assert!(
+                    !values.is_empty(),
+                    "reactive GRASP needs at least one α value"
+                );
+                assert!(*period > 0, "reactive GRASP needs a positive update period");
+                let reactive = Reactive {
+                    values,
+                    period: *period,
+                    probabilities: vec![1. / values.len() as f64; values.len()],
+                    sums: vec![0.; values.len()],
+                    counts: vec![0; values.len()],
+                };
+                (values[0], Some(reactive))
+            }
+        };
+        let mut elite: Vec<DynSolution> = Vec::new();
+        let mut best: Option<DynSolution> = None;
+        let mut iterations = 0;
+
+        while self.max_iterations.is_none_or(|max| iterations < max)
+            && self.time_limit.is_none_or(|limit| now.elapsed() < limit)
+        {
+            iterations += 1;
+
+            let (alpha, index) = match &reactive {
+                Some(reactive) => {
+                    let i = reactive.choose(rng);
+                    (reactive.values[i], Some(i))
+                }
+                None => (fixed, None),
+            };
+            let start = rng.random_range(0..n.max(1));
+            let solution = self.construction.construct(graph, start, alpha, rng);
+            let mut solution = self.refine(&solution, graph, rng);
+
+            if let Some(size) = self.path_relinking {
+                if let Some(guide) = elite.choose(rng)
+                    && let Some(relinked) = relink(&solution, guide, graph)
+                {
+                    let relinked = self.refine(&relinked, graph, rng);
+                    if relinked.cost < solution.cost - EPSILON {
+                        solution = relinked;
+                    }
+                }
+                update_elite(&mut elite, &solution, size);
+            }
+
+            if best
+                .as_ref()
+                .is_none_or(|best| solution.cost < best.cost - EPSILON)
+            {
+                best = Some(solution.clone());
+            }
+            if let (Some(reactive), Some(i)) = (&mut reactive, index) {
+                reactive.record(i, solution.cost);
+                if iterations % reactive.period == 0 {
+                    reactive.update(best.as_ref().unwrap().cost);
+                }
+            }
+        }
+
+        GraspResult {
+            solution: best.unwrap_or_else(|| construction::nearest_insertion(graph, 0)),
+            iterations,
+            probabilities: reactive.map_or_else(Vec::new, |r| r.probabilities),
+        }
+    }
+
+    /// Aplica a busca local de `self.neighbourhood` em `solution`.
+    fn refine<G: DistanceMatrix, R: Rng + ?Sized>(
+        &self,
+        solution: &DynSolution,
+        graph: &G,
+        rng: &mut R,
+    ) -> DynSolution {
+        let start = rng.random_range(0..solution.route.len().max(1));
+        self.neighbourhood.search(solution, graph, start)
+    }
+}
+
+/// Rota `route` rotacionada para começar pelo vértice 0.
+fn normalised(route: &[usize]) -> Vec<usize> {
+    let mut route = route.to_vec();
+    if let Some(i) = route.iter().position(|&v| v == 0) {
+        route.rotate_left(i);
+    }
+    route
+}
+
+/// Insere `solution` no conjunto elite, caso ela ainda não esteja lá e o conjunto tenha espaço
+/// ou ela seja melhor que a pior rota elite, que é substituída.
+fn update_elite(elite: &mut Vec<DynSolution>, solution: &DynSolution, size: usize) {
+    let route = normalised(&solution.route);
+    if size == 0 || elite.iter().any(|e| e.route == route) {
+        return;
+    }
+    let solution = Solution {
+        route,
+        cost: solution.cost,
+    };
+    if elite.len() < size {
+        elite.push(solution);
+    } else if let Some(worst) = elite
+        .iter_mut()
+        .max_by(|a, b| a.cost.total_cmp(&b.cost))
+        .filter(|worst| solution.cost < worst.cost)
+    {
+        *worst = solution;
+    }
+}
+
+/// *Path relinking* de `from` até `guide`: a cada passo, aplica a troca de menor custo entre as
+/// que colocam na sua posição um vértice de `guide` (com as duas rotas começando pelo vértice 0).
+/// Retorna a melhor rota intermediária, ou `None` se as rotas diferem por no máximo uma troca.
+fn relink<G: DistanceMatrix + ?Sized>(
+    from: &DynSolution,
+    guide: &DynSolution,
+    graph: &G,
+) -> Option<DynSolution> {
+    let mut route = normalised(&from.route);
+    let guide = normalised(&guide.route);
+    let mut position = vec![0; route.len()];
+    for (i, &v) in route.iter().enumerate() {
+        position[v] = i;
+    }
+
+    let mut cost = DynSolution::calculate_cost(&route, graph);
+    let mut best: Option<DynSolution> = None;
+    while let Some((i, j, delta)) = (0..route.len())
+        .filter(|&i| route[i] != guide[i])
+        .map(|i| {
+            let j = position[guide[i]];
+            (i, j, Move::Swap(i, j).delta(&route, graph))
+        })
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+    {
+        Move::Swap(i, j).apply(&mut route);
+        (position[route[i]], position[route[j]]) = (i, j);
+        cost += delta;
+
+        // A última troca chega em `guide`, que não é uma rota intermediária.
+        let reached = route.iter().zip(&guide).all(|(u, v)| u == v);
+        if !reached && best.as_ref().is_none_or(|best| cost < best.cost) {
+            best = Some(Solution {
+                route: route.clone(),
+                cost,
+            });
+        }
+    }
+
+    best.map(|best| Solution {
+        cost: DynSolution::calculate_cost(&best.route, graph),
+        ..best
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact;
+    use crate::test_util::{euclidean, is_permutation};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn grasp_test_1() {
+        let graph = euclidean(10, 1);
+        let optimum = exact::held_karp(&graph).unwrap().cost;
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for grasp in [
+            Grasp::default(),
+            Grasp {
+                construction: Greedy::NearestNeighbour,
+                alpha: Alpha::reactive(),
+                neighbourhood: Neighbourhood::OrOpt,
+                path_relinking: Some(5),
+                ..Grasp::default()
+            },
+        ] {
+            let result = grasp.run(&graph, &mut rng);
+
+            assert_eq!(result.iterations, 100);
+            assert!(is_permutation(&result.solution.route));
+            assert!(
+                (result.solution.cost
+                    - DynSolution::calculate_cost(&result.solution.route, &graph))
+                .abs()
+                    < 1e-9
+            );
+            assert!(result.solution.cost >= optimum - 1e-9);
+            assert!(result.solution.cost <= 1.05 * optimum);
+        }
+    }
+
+    #[test]
+    fn grasp_test_2() {
+        let graph = euclidean(15, 2);
+        let mut rng = StdRng::seed_from_u64(2);
+
+        // Com o Vizinho Mais Próximo, o valor 0,0 constrói rotas gulosas, e o 1,0, aleatórias.
+        let result = Grasp {
+            construction: Greedy::NearestNeighbour,
+            alpha: Alpha::Reactive {
+                values: vec![0.0, 1.0],
+                period: 10,
+            },
+            neighbourhood: Neighbourhood::Swap,
+            time_limit: Some(Duration::from_secs(60)),
+            ..Grasp::default()
+        }
+        .run(&graph, &mut rng);
+
+        assert_eq!(result.iterations, 100);
+        assert_eq!(result.probabilities.len(), 2);
+        assert!((result.probabilities.iter().sum::<f64>() - 1.).abs() < 1e-9);
+        assert!(result.probabilities[0] > result.probabilities[1]);
+
+        // Só com o tempo limite.
+        let result = Grasp {
+            max_iterations: None,
+            time_limit: Some(Duration::from_millis(20)),
+            ..Grasp::default()
+        }
+        .run(&graph, &mut rng);
+        assert!(result.iterations > 0);
+        assert!(is_permutation(&result.solution.route));
+        assert!(result.probabilities.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "reactive GRASP needs a positive update period")]
+    fn grasp_test_3() {
+        let grasp = Grasp {
+            alpha: Alpha::Reactive {
+                values: vec![0.1, 0.2],
+                period: 0,
+            },
+            ..Grasp::default()
+        };
+        grasp.run(&euclidean(6, 1), &mut StdRng::seed_from_u64(1));
+    }
+
+    #[test]
+    fn relink_test() {
+        let graph = euclidean(8, 3);
+        let from = DynSolution {
+            route: vec![3, 4, 5, 6, 7, 0, 1, 2],
+            cost: 0.,
+        };
+        let guide = DynSolution {
+            route: vec![0, 2, 1, 4, 3, 6, 5, 7],
+            cost: 0.,
+        };
+
+        let relinked = relink(&from, &guide, &graph).unwrap();
+        assert!(is_permutation(&relinked.route));
+        assert_eq!(relinked.route[0], 0);
+        assert_ne!(relinked.route, guide.route);
+        assert!(
+            (relinked.cost - DynSolution::calculate_cost(&relinked.route, &graph)).abs() < 1e-9
+        );
+
+        // Rotas iguais a menos da rotação não têm rotas intermediárias.
+        assert!(relink(&from, &from, &graph).is_none());
+    }
+}
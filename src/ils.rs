@@ -0,0 +1,398 @@
+//! Busca local iterada (ILS, *Iterated Local Search*).
+//!
+//! A ILS alterna uma perturbação (*kick*) da rota atual com uma busca local completa de
+//! [`LocalSearch`](crate::local_search::LocalSearch). A perturbação precisa ser grande o bastante
+//! para escapar do ótimo local, mas pequena o bastante para que a busca local não desfaça o
+//! movimento: o *double-bridge* é o caso clássico, já que não pode ser desfeito por um 2-opt ou
+//! or-opt. O critério de aceitação decide se a nova rota substitui a atual.
+
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use rand::seq::{IndexedRandom, SliceRandom};
+
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{Neighbourhood, Solution};
+
+/// Melhora mínima para que uma rota substitua a melhor conhecida.
+const EPSILON: f64 = 1e-9;
+
+/// Perturbação aplicada à rota atual a cada iteração.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Kick {
+    /// Corta a rota `A B C D` em quatro trechos, em pontos sorteados, e a reconecta como
+    /// `A C B D`.
+    #[default]
+    DoubleBridge,
+    /// Inverte um trecho sorteado da rota.
+    SegmentReversal,
+}
+
+impl FromStr for Kick {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "double-bridge" => Ok(Self::DoubleBridge),
+            "reversal" => Ok(Self::SegmentReversal),
+            _ => Err(format!(
+                "unknown kick `{s}`, expected `double-bridge` or `reversal`"
+            )),
+        }
+    }
+}
+
+impl Kick {
+    /// Perturba `solution`, que deve ter ao menos 4 vértices.
+    pub fn apply<const N: usize, G: DistanceMatrix + ?Sized, R: Rng + ?Sized>(
+        self,
+        solution: &Solution<N>,
+        graph: &G,
+        rng: &mut R,
+    ) -> Solution<N> {
+        let n = solution.route.len();
+        let route = match self {
+            Self::DoubleBridge => {
+                let cuts: Vec<usize> = (1..n).collect();
+                let mut cuts: Vec<usize> = cuts.choose_multiple(rng, 3).copied().collect();
+                cuts.sort();
+                let (a, b, c) = (cuts[0], cuts[1], cuts[2]);
+                let r = &solution.route;
+                [&r[..a], &r[b..c], &r[a..b], &r[c..]].concat()
+            }
+            Self::SegmentReversal => {
+                let mut route = solution.route.clone();
+                // Inverter n − 1 ou mais vértices consecutivos percorre o mesmo ciclo no sentido
+                // oposto, então o trecho tem entre 2 e n − 2 vértices.
+                let len = rng.random_range(2..=n - 2);
+                let i = rng.random_range(0..=n - len);
+                route[i..i + len].reverse();
+                route
+            }
+        };
+        let cost = Solution::<N>::calculate_cost(&route, graph);
+        Solution { route, cost }
+    }
+}
+
+/// Critério de aceitação da rota obtida após a perturbação e a busca local.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Acceptance {
+    /// Aceita a nova rota se ela não for pior que a atual.
+    #[default]
+    Better,
+    /// Sempre aceita a nova rota.
+    RandomWalk,
+    /// Como `Better`, mas recomeça de uma rota aleatória (refinada pela busca local) após
+    /// `stagnation` iterações sem melhorar a melhor rota.
+    Restart { stagnation: usize },
+    /// LSMC (*Large-Step Markov Chain*): aceita uma rota pior em `Δ` com probabilidade
+    /// `exp(-Δ / (temperature × custo atual))`.
+    Lsmc { temperature: f64 },
+}
+
+impl FromStr for Acceptance {
+    type Err = String;
+
+    /// Critérios com os parâmetros padrão: `better`, `walk`, `restart` e `lsmc`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "better" => Ok(Self::Better),
+            "walk" => Ok(Self::RandomWalk),
+            "restart" => Ok(Self::Restart { stagnation: 100 }),
+            "lsmc" => Ok(Self::Lsmc { temperature: 0.005 }),
+            _ => Err(format!(
+                "unknown acceptance `{s}`, expected `better`, `walk`, `restart` or `lsmc`"
+            )),
+        }
+    }
+}
+
+/// Configuração da ILS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ils {
+    pub kick: Kick,
+    /// Busca local aplicada à rota inicial e após cada perturbação.
+    pub neighbourhood: Neighbourhood,
+    pub acceptance: Acceptance,
+    /// Quantidade máxima de iterações.
+    pub max_iterations: Option<usize>,
+    /// Tempo máximo de execução.
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for Ils {
+    fn default() -> Self {
+        Self {
+            kick: Kick::default(),
+            neighbourhood: Neighbourhood::TwoOpt,
+            acceptance: Acceptance::default(),
+            max_iterations: Some(1000),
+            time_limit: None,
+        }
+    }
+}
+
+/// Momento em que a ILS encontrou uma nova melhor rota.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BestCost {
+    /// Iteração em que a rota foi encontrada; 0 é a busca local da rota inicial.
+    pub iteration: usize,
+    /// Tempo desde o início da busca.
+    pub elapsed: Duration,
+    pub cost: f64,
+}
+
+/// Resultado da ILS.
+#[derive(Clone)]
+pub struct IlsResult<S> {
+    /// Melhor rota encontrada.
+    pub solution: S,
+    /// Quantidade de iterações executadas.
+    pub iterations: usize,
+    /// Evolução do custo da melhor rota, em ordem.
+    pub trace: Vec<BestCost>,
+}
+
+impl Ils {
+    /// Executa a ILS a partir de `solution`.
+    pub fn run<const N: usize, G: DistanceMatrix, R: Rng + ?Sized>(
+        &self,
+        solution: &Solution<N>,
+        graph: &G,
+        rng: &mut R,
+    ) -> IlsResult<Solution<N>> {
+        assert!(
+            self.max_iterations.is_some() || self.time_limit.is_some(),
+            "ILS needs an iteration or time budget"
+        );
+        let now = Instant::now();
+        let n = solution.route.len();
+        let start = Solution {
+            route: solution.route.clone(),
+            cost: Solution::<N>::calculate_cost(&solution.route, graph),
+        };
+        let mut current = self.refine(&start, graph, rng);
+        let mut best = current.clone();
+        let mut trace = vec![BestCost {
+            iteration: 0,
+            elapsed: now.elapsed(),
+            cost: best.cost,
+        }];
+
+        // As perturbações precisam de ao menos 4 vértices.
+        if n < 4 {
+            return IlsResult {
+                solution: best,
+                iterations: 0,
+                trace,
+            };
+        }
+
+        let mut iterations = 0;
+        let mut stall = 0;
+        while self.max_iterations.is_none_or(|max| iterations < max)
+            && self.time_limit.is_none_or(|limit| now.elapsed() < limit)
+        {
+            iterations += 1;
+            let kicked = self.kick.apply(&current, graph, rng);
+            let candidate = self.refine(&kicked, graph, rng);
+
+            if candidate.cost < best.cost - EPSILON {
+                best.clone_from(&candidate);
+                stall = 0;
+                trace.push(BestCost {
+                    iteration: iterations,
+                    elapsed: now.elapsed(),
+                    cost: best.cost,
+                });
+            } else {
+                stall += 1;
+            }
+
+            let not_worse = candidate.cost < current.cost + EPSILON;
+            current = match self.acceptance {
+                Acceptance::Better => {
+                    if not_worse {
+                        candidate
+                    } else {
+                        current
+                    }
+                }
+                Acceptance::RandomWalk => candidate,
+                Acceptance::Restart { stagnation } if stall >= stagnation => {
+                    stall = 0;
+                    let mut route = current.route;
+                    route.shuffle(rng);
+                    let cost = Solution::<N>::calculate_cost(&route, graph);
+                    let restart = self.refine(&Solution { route, cost }, graph, rng);
+                    if restart.cost < best.cost - EPSILON {
+                        best.clone_from(&restart);
+                        trace.push(BestCost {
+                            iteration: iterations,
+                            elapsed: now.elapsed(),
+                            cost: best.cost,
+                        });
+                    }
+                    restart
+                }
+                Acceptance::Restart { .. } => {
+                    if not_worse {
+                        candidate
+                    } else {
+                        current
+                    }
+                }
+                Acceptance::Lsmc { temperature } => {
+                    let delta = candidate.cost - current.cost;
+                    let p = (-delta / (temperature * current.cost)).exp();
+                    if not_worse || rng.random_bool(p.clamp(0., 1.)) {
+                        candidate
+                    } else {
+                        current
+                    }
+                }
+            };
+        }
+
+        IlsResult {
+            solution: best,
+            iterations,
+            trace,
+        }
+    }
+
+    /// Aplica a busca local de `self.neighbourhood` em `solution`.
+    fn refine<const N: usize, G: DistanceMatrix, R: Rng + ?Sized>(
+        &self,
+        solution: &Solution<N>,
+        graph: &G,
+        rng: &mut R,
+    ) -> Solution<N> {
+        let start = rng.random_range(0..solution.route.len().max(1));
+        self.neighbourhood.search(solution, graph, start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact;
+    use crate::local_search::{DynSolution, LocalSearch};
+    use crate::test_util::{euclidean, is_permutation};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    /// Arestas do ciclo, sem sentido.
+    fn edges(route: &[usize]) -> Vec<(usize, usize)> {
+        let n = route.len();
+        let mut edges: Vec<(usize, usize)> = (0..n)
+            .map(|i| {
+                let (u, v) = (route[i], route[(i + 1) % n]);
+                (u.min(v), u.max(v))
+            })
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn kick_test() {
+        let graph = euclidean(8, 1);
+        let solution = DynSolution {
+            route: (0..8).collect(),
+            cost: 0.,
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..100 {
+            for kick in [Kick::DoubleBridge, Kick::SegmentReversal] {
+                let kicked = kick.apply(&solution, &graph, &mut rng);
+                assert!(is_permutation(&kicked.route));
+                assert_ne!(edges(&kicked.route), edges(&solution.route));
+                assert_eq!(
+                    kicked.cost,
+                    DynSolution::calculate_cost(&kicked.route, &graph)
+                );
+            }
+
+            // O double-bridge troca exatamente 4 arestas, e os trechos mantêm o sentido.
+            let kicked = Kick::DoubleBridge.apply(&solution, &graph, &mut rng);
+            let breaks = (0..8)
+                .filter(|&i| kicked.route[(i + 1) % 8] != (kicked.route[i] + 1) % 8)
+                .count();
+            assert!((3..=4).contains(&breaks));
+        }
+    }
+
+    #[test]
+    fn ils_test_1() {
+        let graph = euclidean(12, 2);
+        let optimum = exact::held_karp(&graph).unwrap().cost;
+        let start = DynSolution {
+            route: (0..12).collect(),
+            cost: 0.,
+        };
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for acceptance in ["better", "walk", "restart", "lsmc"] {
+            let ils = Ils {
+                acceptance: acceptance.parse().unwrap(),
+                max_iterations: Some(300),
+                ..Ils::default()
+            };
+            let result = ils.run(&start, &graph, &mut rng);
+
+            assert_eq!(result.iterations, 300);
+            assert!(is_permutation(&result.solution.route));
+            assert!(result.solution.cost >= optimum - 1e-9);
+            assert!(result.solution.cost <= 1.02 * optimum, "{acceptance}");
+            assert!(result.solution.cost <= start.two_opt(&graph).cost + 1e-9);
+
+            // O traço é decrescente e termina no custo da melhor rota.
+            assert_eq!(result.trace[0].iteration, 0);
+            assert!(result.trace.windows(2).all(|w| w[1].cost < w[0].cost
+                && w[1].iteration > w[0].iteration
+                && w[1].elapsed >= w[0].elapsed));
+            assert_eq!(result.trace.last().unwrap().cost, result.solution.cost);
+        }
+    }
+
+    #[test]
+    fn ils_test_2() {
+        let graph = euclidean(30, 3);
+        let start = DynSolution {
+            route: (0..30).collect(),
+            cost: 0.,
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let result = Ils {
+            kick: Kick::SegmentReversal,
+            neighbourhood: Neighbourhood::OrOpt,
+            acceptance: Acceptance::Restart { stagnation: 5 },
+            max_iterations: None,
+            time_limit: Some(Duration::from_millis(50)),
+        }
+        .run(&start, &graph, &mut rng);
+
+        assert!(result.iterations > 0);
+        assert!(is_permutation(&result.solution.route));
+        assert_eq!(
+            result.solution.cost,
+            DynSolution::calculate_cost(&result.solution.route, &graph)
+        );
+
+        // Recomeçando a cada iteração, as melhorias vindas dos recomeços também são registradas.
+        let result = Ils {
+            kick: Kick::SegmentReversal,
+            neighbourhood: Neighbourhood::Swap,
+            acceptance: Acceptance::Restart { stagnation: 0 },
+            max_iterations: Some(50),
+            time_limit: None,
+        }
+        .run(&start, &graph, &mut rng);
+        assert_eq!(result.trace.last().unwrap().cost, result.solution.cost);
+        assert!(result.trace.windows(2).all(|w| w[1].cost < w[0].cost));
+    }
+}
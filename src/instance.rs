@@ -0,0 +1,182 @@
+//! Carregamento de instâncias em tempo de execução.
+//!
+//! Lê o mesmo formato de matriz de adjacência em CSV usado pela macro `graph_from_csv!`
+//! (`data/NNN/data.csv`): a primeira linha é um cabeçalho com os rótulos das cidades e cada linha
+//! seguinte começa com o rótulo da cidade, seguido dos custos para todas as outras. Apenas a
+//! diagonal pode ficar vazia, sendo lida como custo zero. Os rótulos do cabeçalho são preservados em
+//! [`Instance::labels`], para que as rotas possam ser exibidas com as cidades originais.
+//!
+//! Instâncias no formato TSPLIB (arquivos `.tsp`) também podem ser carregadas por [`load`], que
+//! delega a leitura para [`crate::tsplib`].
+
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use crate::graphs::Matrix;
+use crate::tsplib::{self, Tsplib};
+
+/// Instância do Caixeiro Viajante carregada em tempo de execução.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    /// Rótulos originais das cidades: o vértice `i` da matriz é a cidade `labels[i]`.
+    pub labels: Vec<String>,
+    /// Matriz de distâncias entre as cidades.
+    pub matrix: Matrix,
+    /// Coordenadas das cidades, quando a instância as fornece (e.g. TSPLIB `EUC_2D`).
+    pub coords: Option<Vec<(f64, f64)>>,
+}
+
+impl From<Tsplib> for Instance {
+    /// Os vértices de uma instância TSPLIB são numerados a partir de 1.
+    fn from(tsp: Tsplib) -> Self {
+        Self {
+            labels: (1..=tsp.matrix.len()).map(|i| i.to_string()).collect(),
+            matrix: tsp.matrix,
+            coords: tsp.coords,
+        }
+    }
+}
+
+/// Carrega uma instância, escolhendo o formato pela extensão do arquivo: `.tsp` para TSPLIB e
+/// CSV para qualquer outra.
+pub fn load(path: impl AsRef<Path>) -> io::Result<Instance> {
+    let path = path.as_ref();
+    if path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("tsp"))
+    {
+        Ok(tsplib::read(path)?.into())
+    } else {
+        read_csv(path)
+    }
+}
+
+/// Lê uma instância em CSV do caminho `path`.
+pub fn read_csv(path: impl AsRef<Path>) -> io::Result<Instance> {
+    parse_csv(&fs::read_to_string(path)?)
+}
+
+/// Interpreta o conteúdo de uma instância em CSV.
+///
+/// # Erros
+/// Retorna um erro do tipo [`ErrorKind::InvalidData`] caso algum custo fora da diagonal esteja
+/// vazio ou não seja um número, caso o rótulo de alguma linha seja diferente do rótulo da coluna
+/// correspondente no cabeçalho ou caso a matriz não seja quadrada.
+pub fn parse_csv(content: &str) -> io::Result<Instance> {
+    let labels: Vec<String> = content
+        .lines()
+        .next()
+        .into_iter()
+        .flat_map(|header| header.split(',').skip(1))
+        .map(|label| label.trim().to_string())
+        .collect();
+    let mut rows = Vec::new();
+
+    let lines = content.lines().skip(1).filter(|l| !l.trim().is_empty());
+    for (i, line) in lines.enumerate() {
+        let mut records = line.split(',').map(str::trim);
+        let row_label = records.next().unwrap_or_default();
+        if labels.get(i).is_some_and(|l| l != row_label) {
+            return Err(invalid_data(format!(
+                "row {} is labelled `{row_label}`, but column {} of the header is `{}`",
+                i + 1,
+                i + 1,
+                labels[i]
+            )));
+        }
+
+        let mut row = Vec::new();
+        for (j, record) in records.enumerate() {
+            row.push(match record.parse() {
+                Ok(w) => w,
+                Err(_) if record.is_empty() && i == j => 0.,
+                Err(_) if record.is_empty() => {
+                    return Err(invalid_data(format!(
+                        "row {} (`{row_label}`), column {}: missing weight",
+                        i + 1,
+                        j + 1
+                    )));
+                }
+                Err(_) => {
+                    return Err(invalid_data(format!(
+                        "row {} (`{row_label}`), column {}: `{record}` is not a number",
+                        i + 1,
+                        j + 1
+                    )));
+                }
+            });
+        }
+        rows.push(row);
+    }
+
+    if let Some((i, row)) = rows.iter().enumerate().find(|(_, r)| r.len() != rows.len()) {
+        return Err(invalid_data(format!(
+            "row {} has {} entries, expected {}",
+            i + 1,
+            row.len(),
+            rows.len()
+        )));
+    }
+
+    if labels.len() != rows.len() {
+        return Err(invalid_data(format!(
+            "header has {} labels, expected {}",
+            labels.len(),
+            rows.len()
+        )));
+    }
+
+    Ok(Instance {
+        labels,
+        matrix: Matrix::from_rows(rows),
+        coords: None,
+    })
+}
+
+pub(crate) fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::DistanceMatrix;
+
+    #[test]
+    fn parse_csv_test_1() {
+        let csv = "Km,1,7,8\n1,,170,26.4\n7,170,,166\n8,26.4,166,\n";
+
+        let Instance {
+            labels,
+            matrix: graph,
+            coords,
+        } = parse_csv(csv).unwrap();
+
+        assert_eq!(labels, ["1", "7", "8"]);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.dist(0, 0), 0.0);
+        assert_eq!(graph.dist(0, 2), 26.4);
+        assert_eq!(graph[2][1], 166.0);
+        assert!(coords.is_none());
+    }
+
+    #[test]
+    fn parse_csv_test_2() {
+        assert!(parse_csv("Km,1,2\n1,,abc\n2,3,\n").is_err());
+        assert!(parse_csv("Km,1,2\n1,,3\n2,3\n").is_err());
+        assert!(parse_csv("Km,1,2\n1,,\n2,3,\n").is_err());
+        assert!(parse_csv("Km,1,2\n1,,3\n7,3,\n").is_err());
+    }
+
+    #[test]
+    fn read_csv_test() {
+        let instance = read_csv("data/009/data.csv").unwrap();
+
+        assert_eq!(instance.labels, ["1", "7", "8", "9", "10", "11", "12"]);
+        assert_eq!(instance.matrix.node_count(), 7);
+        assert_eq!(instance.matrix[0][1], 170.0);
+    }
+}
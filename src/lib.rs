@@ -3,17 +3,58 @@
 //! Crate que fornece algoritmos e estruturas de dados para Grafos.
 //!
 //! A organização do crate é feita em diversos módulos:
-//! - `adjacency_list`: guarda a representação de um grafo no formato da Lista de Adjacência,
-//!   bastante popular e comum de implementar;
-//! - `traversal`: armazena os algoritmos para a travessia em um grafo. Os algoritmos incluem a
-//!   BFS, DFS, DFS com classificação de arestas e identificação de componentes.
+//! - `aco`: colônia de formigas nas variantes *Ant System*, *MAX-MIN Ant System* e *Ant Colony
+//!   System*, com listas de candidatos e busca local opcional;
+//! - `annealing`: têmpera simulada sobre movimentos aleatórios de swap, shift, 2-opt e or-opt,
+//!   com esquemas de resfriamento configuráveis;
+//! - `analysis`: verifica se uma instância é métrica (simetria, desigualdade triangular, custos
+//!   negativos ou infinitos) e resume estatísticas dos custos das arestas;
+//! - `candidates`: listas com os vizinhos mais próximos de cada vértice e buscas locais restritas a
+//!   elas, com *don't-look bits*;
+//! - `christofides`: algoritmo de Christofides, com garantia de 1,5 vez o ótimo em instâncias
+//!   métricas;
+//! - `construction`: heurísticas construtivas (Vizinho Mais Próximo e a família de heurísticas de
+//!   inserção: mais próxima, mais distante, mais barata, aleatória e pelo fecho convexo) e
+//!   baseadas em arestas (gulosa e economias de Clarke–Wright), além das versões semigulosas do
+//!   Vizinho Mais Próximo e da Inserção Mais Próxima;
+//! - `exact`: solvers exatos: programação dinâmica (Held–Karp) e *branch and bound*;
+//! - `graphs`: guarda a representação de um grafo completo por matriz de distâncias, seja ela
+//!   conhecida em tempo de compilação ou carregada em tempo de execução;
+//! - `grasp`: GRASP com construção semigulosa, `α` fixo ou reativo e *path relinking* opcional;
+//! - `ils`: busca local iterada, com perturbação *double-bridge* ou inversão de trecho e critérios
+//!   de aceitação configuráveis;
+//! - `instance`: carrega instâncias do Caixeiro Viajante em tempo de execução;
+//! - `mst`: árvore geradora mínima (Prim), o limitante inferior dado pelo seu peso e a heurística
+//!   da árvore dupla;
+//! - `one_tree`: limitante inferior de Held–Karp, pela 1-árvore com otimização por subgradiente;
+//! - `tabu`: busca tabu sobre as vizinhanças swap e 2-opt, com atributos de arestas ou posições,
+//!   aspiração e diversificação por frequência;
+//! - `tsplib`: lê instâncias no formato TSPLIB95, com custos explícitos ou por coordenadas;
+//! - `lin_kernighan`: busca local de profundidade variável no estilo Lin–Kernighan;
+//! - `local_search`: armazena as buscas locais (swap, shift, 2-opt, or-opt, 3-opt e or-3opt) sobre
+//!   uma `Solution`;
+//! - `vnd`: descida em vizinhança variável sobre uma lista ordenada de vizinhanças.
 
 #![feature(impl_trait_in_assoc_type)]
 
-// mod heuristics;
+pub mod aco;
+pub mod analysis;
+pub mod annealing;
+pub mod candidates;
+pub mod christofides;
+pub mod construction;
+pub mod exact;
+pub mod graphs;
+pub mod grasp;
+pub mod ils;
+pub mod instance;
+pub mod lin_kernighan;
 pub mod local_search;
+pub mod mst;
+pub mod one_tree;
+pub mod tabu;
+pub mod tsplib;
+pub mod vnd;
 
-pub mod graphs {
-    // pub use crate::heuristics::nearest_neighbour;
-    pub type Graph = Vec<Vec<f64>>;
-}
+#[cfg(test)]
+pub(crate) mod test_util;
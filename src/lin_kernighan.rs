@@ -0,0 +1,299 @@
+//! Busca local de profundidade variável no estilo Lin–Kernighan.
+//!
+//! Cada movimento do Lin–Kernighan é uma cadeia de movimentos 2-opt sequenciais: a partir de um
+//! vértice fixo `t1`, remove-se a aresta `(t1, t2)` e, a cada passo, escolhe-se uma nova aresta
+//! `(t2, t3)` a inserir e a aresta `(t4, t3)` a remover, de forma que o tour sempre possa ser
+//! fechado com `(t4, t1)`. A cadeia cresce enquanto o ganho parcial for positivo e, ao final, é
+//! mantido apenas o prefixo que resultou no melhor tour. Os vértices `t3` são procurados apenas
+//! entre os `k` vizinhos mais próximos de `t2`, e o primeiro nível da cadeia testa mais de uma
+//! alternativa (*backtracking*).
+//!
+//! A busca assume custos simétricos e trabalha sobre os vértices da rota recebida, de forma que
+//! também pode ser usada em rotas que visitam apenas parte do grafo.
+
+use std::time::{Duration, Instant};
+
+use crate::candidates::CandidateList;
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{Descent, Solution};
+
+/// Ganho mínimo para que uma cadeia de movimentos seja aplicada.
+const EPSILON: f64 = 1e-9;
+
+/// Parâmetros da busca Lin–Kernighan.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinKernighan {
+    /// Quantidade de vizinhos mais próximos considerados como candidatos a `t3`; `0` considera
+    /// todos os vértices.
+    pub candidates: usize,
+    /// Quantidade de alternativas testadas no primeiro nível de cada cadeia.
+    pub breadth: usize,
+    /// Quantidade máxima de movimentos 2-opt em uma cadeia.
+    pub max_depth: usize,
+    /// Tempo máximo de execução; `None` executa até um ótimo local.
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for LinKernighan {
+    fn default() -> Self {
+        Self {
+            candidates: 8,
+            breadth: 5,
+            max_depth: 50,
+            time_limit: None,
+        }
+    }
+}
+
+impl LinKernighan {
+    /// Melhora a rota de `solution` até que nenhuma cadeia de movimentos melhore o tour ou até
+    /// esgotar o tempo limite.
+    ///
+    /// Em [`Descent`], `moves` é a quantidade de cadeias aplicadas e `local_optimum` indica se a
+    /// busca terminou antes do tempo limite.
+    pub fn improve<const N: usize, G: DistanceMatrix + ?Sized>(
+        &self,
+        solution: &Solution<N>,
+        graph: &G,
+    ) -> Descent<Solution<N>> {
+        let now = Instant::now();
+        let cities = &solution.route;
+        let n = cities.len();
+
+        if n < 4 {
+            return Descent {
+                solution: Solution {
+                    route: cities.clone(),
+                    cost: Solution::<N>::calculate_cost(cities, graph),
+                },
+                moves: 0,
+                local_optimum: true,
+            };
+        }
+
+        // Os vértices são identificados pela sua posição na rota original.
+        let d = |a: usize, b: usize| graph.dist(cities[a], cities[b]);
+        let k = if self.candidates == 0 {
+            n - 1
+        } else {
+            self.candidates.min(n - 1)
+        };
+        let candidates = CandidateList::from_fn(n, k, d);
+
+        let mut tour = Tour::new(n);
+        let mut moves = 0;
+        let mut local_optimum = true;
+        let mut improved = true;
+
+        'search: while improved {
+            improved = false;
+            for t1 in 0..n {
+                if self.time_limit.is_some_and(|t| now.elapsed() >= t) {
+                    local_optimum = false;
+                    break 'search;
+                }
+                for forward in [true, false] {
+                    if self.chain(&mut tour, t1, forward, &d, &candidates) {
+                        moves += 1;
+                        improved = true;
+                    }
+                }
+            }
+        }
+
+        // Mantém a rota começando pela mesma cidade que a rota original.
+        let first = tour.pos[0];
+        let route: Vec<usize> = (0..n)
+            .map(|i| cities[tour.order[(first + i) % n]])
+            .collect();
+        let cost = Solution::<N>::calculate_cost(&route, graph);
+        Descent {
+            solution: Solution { route, cost },
+            moves,
+            local_optimum,
+        }
+    }
+
+    /// Tenta uma cadeia de movimentos a partir de `t1`, removendo a aresta entre `t1` e o seu
+    /// sucessor na orientação `forward`. Retorna se o tour foi melhorado.
+    fn chain(
+        &self,
+        tour: &mut Tour,
+        t1: usize,
+        forward: bool,
+        d: &impl Fn(usize, usize) -> f64,
+        candidates: &CandidateList,
+    ) -> bool {
+        let t2 = tour.next(t1, forward);
+        let g0 = d(t1, t2);
+
+        // Alternativas do primeiro nível, ordenadas pelo ganho após remover `(t4, t3)`.
+        let mut options: Vec<(f64, usize)> = candidates
+            .neighbours(t2)
+            .iter()
+            .filter(|&&t3| g0 - d(t2, t3) > EPSILON)
+            .filter(|&&t3| t3 != t1 && t3 != tour.next(t2, forward))
+            .map(|&t3| (g0 - d(t2, t3) + d(tour.prev(t3, forward), t3), t3))
+            .collect();
+        options.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        for &(_, first_t3) in options.iter().take(self.breadth.max(1)) {
+            let mut log: Vec<(usize, usize)> = Vec::new();
+            let mut added = vec![(t2, first_t3)];
+            let (mut g, mut t2, mut t3) = (g0, t2, first_t3);
+            let (mut best_gain, mut best_len) = (EPSILON, 0);
+
+            loop {
+                // Remove `(t4, t3)`, insere `(t2, t3)` e fecha o tour com `(t4, t1)`, invertendo o
+                // caminho de `t2` até `t4`.
+                let t4 = tour.prev(t3, forward);
+                g += d(t4, t3) - d(t2, t3);
+                let (i, j) = if forward {
+                    (tour.pos[t2], tour.pos[t4])
+                } else {
+                    (tour.pos[t4], tour.pos[t2])
+                };
+                tour.reverse(i, j);
+                log.push((i, j));
+
+                if g - d(t4, t1) > best_gain {
+                    best_gain = g - d(t4, t1);
+                    best_len = log.len();
+                }
+                if log.len() >= self.max_depth {
+                    break;
+                }
+
+                // O próximo passo remove a aresta de fechamento `(t1, t4)`.
+                t2 = t4;
+                let next = candidates
+                    .neighbours(t2)
+                    .iter()
+                    .filter(|&&t5| g - d(t2, t5) > EPSILON)
+                    .filter(|&&t5| t5 != t1 && t5 != tour.next(t2, forward))
+                    .filter(|&&t5| {
+                        let t6 = tour.prev(t5, forward);
+                        !added.contains(&(t6, t5)) && !added.contains(&(t5, t6))
+                    })
+                    .map(|&t5| (g - d(t2, t5) + d(tour.prev(t5, forward), t5), t5))
+                    .max_by(|a, b| a.0.total_cmp(&b.0));
+                match next {
+                    Some((_, t5)) => {
+                        added.push((t2, t5));
+                        t3 = t5;
+                    }
+                    None => break,
+                }
+            }
+
+            // Desfaz os movimentos posteriores ao melhor tour encontrado na cadeia.
+            for &(i, j) in log[best_len..].iter().rev() {
+                tour.reverse(i, j);
+            }
+            if best_len > 0 {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// Tour representado por um vetor de vértices e pela posição de cada vértice nele.
+struct Tour {
+    order: Vec<usize>,
+    pos: Vec<usize>,
+}
+
+impl Tour {
+    fn new(n: usize) -> Self {
+        Self {
+            order: (0..n).collect(),
+            pos: (0..n).collect(),
+        }
+    }
+
+    /// Sucessor de `v` na orientação `forward` (ou predecessor, na orientação contrária).
+    fn next(&self, v: usize, forward: bool) -> usize {
+        let n = self.order.len();
+        let step = if forward { 1 } else { n - 1 };
+        self.order[(self.pos[v] + step) % n]
+    }
+
+    fn prev(&self, v: usize, forward: bool) -> usize {
+        self.next(v, !forward)
+    }
+
+    /// Inverte o trecho circular do tour que vai da posição `i` até a posição `j`.
+    fn reverse(&mut self, i: usize, j: usize) {
+        let n = self.order.len();
+        let len = (j + n - i) % n + 1;
+        for s in 0..len / 2 {
+            let (a, b) = ((i + s) % n, (j + n - s) % n);
+            self.order.swap(a, b);
+            self.pos[self.order[a]] = a;
+            self.pos[self.order[b]] = b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::Matrix;
+    use crate::local_search::{DynSolution, LocalSearch};
+    use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn lin_kernighan_test_1() {
+        // Vértices sobre um círculo: o ótimo é percorrê-los em ordem.
+        let n = 30;
+        let points: Vec<(f64, f64)> = (0..n)
+            .map(|i| {
+                let a = 2. * PI * i as f64 / n as f64;
+                (a.cos() * 100., a.sin() * 100.)
+            })
+            .collect();
+        let graph = Matrix::from_fn(n, |u, v| {
+            let (dx, dy) = (points[u].0 - points[v].0, points[u].1 - points[v].1);
+            (dx * dx + dy * dy).sqrt()
+        });
+        let optimum = DynSolution::calculate_cost(&(0..n).collect::<Vec<_>>(), &graph);
+
+        let mut route: Vec<usize> = (0..n).collect();
+        route.shuffle(&mut StdRng::seed_from_u64(7));
+        let solution = DynSolution {
+            cost: DynSolution::calculate_cost(&route, &graph),
+            route,
+        };
+
+        let d = LinKernighan::default().improve(&solution, &graph);
+        assert!(d.local_optimum);
+        assert!(d.moves > 0);
+        assert!((d.solution.cost - optimum).abs() < 1e-6);
+        assert_eq!(d.solution.route[0], solution.route[0]);
+
+        let mut visited = d.solution.route.clone();
+        visited.sort();
+        assert_eq!(visited, (0..n).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn lin_kernighan_test_2() {
+        let graph = vec![
+            vec![f64::INFINITY, 1.0, 2.0, 4.0, 3.0],
+            vec![1.0, f64::INFINITY, 7.0, 2.0, 5.0],
+            vec![2.0, 7.0, f64::INFINITY, 8.0, 1.0],
+            vec![4.0, 2.0, 8.0, f64::INFINITY, 6.0],
+            vec![3.0, 5.0, 1.0, 6.0, f64::INFINITY],
+        ];
+
+        let solution = DynSolution {
+            route: vec![0, 1, 2, 3, 4],
+            cost: 25.0,
+        };
+
+        assert_eq!(solution.lin_kernighan(&graph).cost, 12.0);
+    }
+}
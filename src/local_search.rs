@@ -1,11 +1,232 @@
+use std::{fmt, iter, str::FromStr};
+
+use crate::candidates::{self, CandidateList};
+use crate::graphs::DistanceMatrix;
+use crate::lin_kernighan::LinKernighan;
+
+/// Buscas locais sobre uma solução do Caixeiro Viajante.
+///
+/// Cada vizinhança possui uma versão `*_with`, que recebe a [`Policy`] de pivoteamento e relata
+/// como a busca terminou em um [`Descent`], e uma versão simples, que usa a política padrão de
+/// cada operador e retorna apenas a solução final.
 #[allow(dead_code)]
-pub trait LocalSearch<Graph> {
-    fn swap(&self, graph: &Graph, start: usize) -> Self;
-    fn two_opt(&self, graph: &Graph) -> Self;
-    fn shift(&self, graph: &Graph, start: usize) -> Self;
-    fn or_opt(&self, graph: &Graph) -> Self;
+pub trait LocalSearch<Graph: ?Sized>: Sized {
+    fn swap_with(&self, graph: &Graph, start: usize, policy: Policy) -> Descent<Self>;
+    fn two_opt_with(&self, graph: &Graph, policy: Policy) -> Descent<Self>;
+    fn shift_with(&self, graph: &Graph, start: usize, policy: Policy) -> Descent<Self>;
+    fn or_opt_with(&self, graph: &Graph, policy: Policy) -> Descent<Self>;
+    fn three_opt_with(&self, graph: &Graph, policy: Policy) -> Descent<Self>;
+    fn or_3opt_with(&self, graph: &Graph, policy: Policy) -> Descent<Self>;
+    fn lin_kernighan_with(&self, graph: &Graph, params: &LinKernighan) -> Descent<Self>;
+
+    /// 2-opt que só avalia movimentos que inserem arestas de `candidates`, usando *don't-look
+    /// bits*, até um ótimo local. Adequado para instâncias com milhares de vértices.
+    ///
+    /// Nas versões com candidatos, `local_optimum` sempre é `true`, mas indica um ótimo local
+    /// apenas da vizinhança restrita: ainda pode haver movimentos de melhora na vizinhança
+    /// completa que insiram arestas fora de `candidates`.
+    fn two_opt_with_candidates(&self, graph: &Graph, candidates: &CandidateList) -> Descent<Self>;
+    /// Or-opt restrito às arestas de `candidates`, como em
+    /// [`LocalSearch::two_opt_with_candidates`].
+    fn or_opt_with_candidates(&self, graph: &Graph, candidates: &CandidateList) -> Descent<Self>;
+    /// Shift restrito às arestas de `candidates`, aplicado a todos os vértices da rota.
+    fn shift_with_candidates(&self, graph: &Graph, candidates: &CandidateList) -> Descent<Self>;
+
+    fn swap(&self, graph: &Graph, start: usize) -> Self {
+        self.swap_with(graph, start, Policy::default()).solution
+    }
+
+    /// 2-opt com *first-improvement* até um ótimo local.
+    fn two_opt(&self, graph: &Graph) -> Self {
+        self.two_opt_with(graph, Policy::first_improvement())
+            .solution
+    }
+
+    fn shift(&self, graph: &Graph, start: usize) -> Self {
+        self.shift_with(graph, start, Policy::default()).solution
+    }
+
+    fn or_opt(&self, graph: &Graph) -> Self {
+        self.or_opt_with(graph, Policy::default()).solution
+    }
+
+    /// 3-opt com *first-improvement* até um ótimo local, já que a vizinhança tem O(n³) movimentos.
+    fn three_opt(&self, graph: &Graph) -> Self {
+        self.three_opt_with(graph, Policy::first_improvement())
+            .solution
+    }
+
+    /// Or-opt que também tenta reinserir cada trecho invertido.
+    fn or_3opt(&self, graph: &Graph) -> Self {
+        self.or_3opt_with(graph, Policy::default()).solution
+    }
+
+    /// Lin–Kernighan com os parâmetros padrão, até um ótimo local.
+    fn lin_kernighan(&self, graph: &Graph) -> Self {
+        self.lin_kernighan_with(graph, &LinKernighan::default())
+            .solution
+    }
+}
+
+/// Vizinhanças disponíveis em [`LocalSearch`], para que possam ser escolhidas em tempo de
+/// execução (e.g. pelos argumentos de um binário).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    Swap,
+    Shift,
+    TwoOpt,
+    OrOpt,
+    ThreeOpt,
+    Or3Opt,
+    LinKernighan,
 }
 
+impl Neighbourhood {
+    /// Todas as vizinhanças, da menor para a maior.
+    pub const ALL: [Neighbourhood; 7] = [
+        Self::Swap,
+        Self::Shift,
+        Self::TwoOpt,
+        Self::OrOpt,
+        Self::Or3Opt,
+        Self::ThreeOpt,
+        Self::LinKernighan,
+    ];
+
+    /// Executa a busca local desta vizinhança com a política padrão do operador. As vizinhanças
+    /// `Swap` e `Shift` movem apenas o vértice da posição `start`.
+    pub fn search<S: LocalSearch<G>, G>(self, solution: &S, graph: &G, start: usize) -> S {
+        match self {
+            Self::Swap => solution.swap(graph, start),
+            Self::Shift => solution.shift(graph, start),
+            Self::TwoOpt => solution.two_opt(graph),
+            Self::OrOpt => solution.or_opt(graph),
+            Self::ThreeOpt => solution.three_opt(graph),
+            Self::Or3Opt => solution.or_3opt(graph),
+            Self::LinKernighan => solution.lin_kernighan(graph),
+        }
+    }
+
+    /// Como [`Neighbourhood::search`], mas com a política `policy`. O Lin–Kernighan não usa
+    /// regras de pivoteamento e ignora `policy`.
+    pub fn search_with<S: LocalSearch<G>, G>(
+        self,
+        solution: &S,
+        graph: &G,
+        start: usize,
+        policy: Policy,
+    ) -> Descent<S> {
+        match self {
+            Self::Swap => solution.swap_with(graph, start, policy),
+            Self::Shift => solution.shift_with(graph, start, policy),
+            Self::TwoOpt => solution.two_opt_with(graph, policy),
+            Self::OrOpt => solution.or_opt_with(graph, policy),
+            Self::ThreeOpt => solution.three_opt_with(graph, policy),
+            Self::Or3Opt => solution.or_3opt_with(graph, policy),
+            Self::LinKernighan => solution.lin_kernighan_with(graph, &LinKernighan::default()),
+        }
+    }
+}
+
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|n| n.to_string() == s)
+            .ok_or_else(|| format!("unknown neighbourhood `{s}`"))
+    }
+}
+
+impl fmt::Display for Neighbourhood {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Swap => "swap",
+            Self::Shift => "shift",
+            Self::TwoOpt => "2opt",
+            Self::OrOpt => "oropt",
+            Self::ThreeOpt => "3opt",
+            Self::Or3Opt => "or3opt",
+            Self::LinKernighan => "lk",
+        })
+    }
+}
+
+/// Regra de pivoteamento: qual movimento de melhora é aplicado a cada passo da busca.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pivoting {
+    /// Aplica o primeiro movimento de melhora encontrado na vizinhança.
+    FirstImprovement,
+    /// Avalia a vizinhança inteira e aplica o movimento de maior melhora.
+    #[default]
+    BestImprovement,
+}
+
+impl FromStr for Pivoting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(Self::FirstImprovement),
+            "best" => Ok(Self::BestImprovement),
+            _ => Err(format!(
+                "unknown pivoting rule `{s}`, expected `first` or `best`"
+            )),
+        }
+    }
+}
+
+/// Política de uma busca local: a regra de pivoteamento e quantos movimentos podem ser aplicados.
+///
+/// A política padrão é *best-improvement* até um ótimo local.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Policy {
+    pub pivoting: Pivoting,
+    /// Quantidade máxima de movimentos aplicados; `None` itera até um ótimo local.
+    pub max_moves: Option<usize>,
+}
+
+impl Policy {
+    pub fn first_improvement() -> Self {
+        Self {
+            pivoting: Pivoting::FirstImprovement,
+            max_moves: None,
+        }
+    }
+
+    pub fn best_improvement() -> Self {
+        Self {
+            pivoting: Pivoting::BestImprovement,
+            max_moves: None,
+        }
+    }
+
+    /// Limita a busca a no máximo `max_moves` movimentos.
+    pub fn with_max_moves(self, max_moves: usize) -> Self {
+        Self {
+            max_moves: Some(max_moves),
+            ..self
+        }
+    }
+}
+
+/// Resultado de uma busca local.
+#[derive(Clone)]
+pub struct Descent<S> {
+    /// Melhor solução encontrada.
+    pub solution: S,
+    /// Quantidade de movimentos de melhora aplicados.
+    pub moves: usize,
+    /// Indica se a busca parou por não haver mais movimentos de melhora, isto é, se `solution` é
+    /// um ótimo local da vizinhança. É `false` quando a busca parou pelo limite de movimentos.
+    pub local_optimum: bool,
+}
+
+/// Uma rota (ciclo hamiltoniano) e o seu custo.
+///
+/// O parâmetro `N` é a quantidade de vértices quando a instância é conhecida em tempo de
+/// compilação; para instâncias carregadas em tempo de execução use [`DynSolution`].
 #[derive(PartialEq, Clone)]
 #[allow(dead_code)]
 pub struct Solution<const N: usize> {
@@ -13,211 +234,451 @@ pub struct Solution<const N: usize> {
     pub cost: f64,
 }
 
+/// Solução para instâncias cujo tamanho só é conhecido em tempo de execução.
+pub type DynSolution = Solution<0>;
+
+/// Rota exibida com os rótulos originais das cidades. Criada por [`Solution::display`].
+pub struct LabelledRoute<'a, L> {
+    route: &'a [usize],
+    labels: &'a [L],
+}
+
+impl<L: AsRef<str>> fmt::Display for LabelledRoute<'_, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &v in self.route {
+            write!(f, "{} -> ", self.labels[v].as_ref())?;
+        }
+        match self.route.first() {
+            Some(&v) => write!(f, "{}", self.labels[v].as_ref()),
+            None => Ok(()),
+        }
+    }
+}
+
 #[allow(dead_code)]
 impl<const N: usize> Solution<N> {
-    pub fn calculate_cost(route: &[usize], graph: &[[f64; N]; N]) -> f64 {
+    pub fn calculate_cost<G: DistanceMatrix + ?Sized>(route: &[usize], graph: &G) -> f64 {
         if route.is_empty() {
             return 0.0;
         }
 
-        route.windows(2).map(|w| graph[w[0]][w[1]]).sum::<f64>()
-            + graph[route[route.len() - 1]][route[0]]
+        route
+            .windows(2)
+            .map(|w| graph.dist(w[0], w[1]))
+            .sum::<f64>()
+            + graph.dist(route[route.len() - 1], route[0])
     }
 
-    fn neighbourhood_by_swap(&self, graph: &[[f64; N]; N], start: usize) -> Vec<Self> {
-        let mut solutions: Vec<Solution<N>> = Vec::new();
-
-        for v in &self.route {
-            let mut new_route = self.route.clone();
-
-            new_route.swap(start, *v);
-            // let tmp = new_route[start];
-            // new_route[start] = new_route[*v];
-            // new_route[*v] = tmp;
-
-            let cost = Self::calculate_cost(&new_route, graph);
+    /// Rótulos originais das cidades da rota, na ordem em que são visitadas.
+    pub fn route_labels<'a, L: AsRef<str>>(&self, labels: &'a [L]) -> Vec<&'a str> {
+        self.route.iter().map(|&v| labels[v].as_ref()).collect()
+    }
 
-            solutions.push(Self {
-                route: new_route,
-                cost,
-            });
+    /// Exibe a rota com os rótulos originais das cidades, fechando o ciclo (e.g. `1 -> 8 -> 1`).
+    pub fn display<'a, L: AsRef<str>>(&'a self, labels: &'a [L]) -> LabelledRoute<'a, L> {
+        LabelledRoute {
+            route: &self.route,
+            labels,
         }
-        solutions
     }
 
-    fn neighbourhood_by_shift(&self, graph: &[[f64; N]; N], start: usize) -> Vec<Self> {
-        let mut solutions: Vec<Solution<N>> = Vec::new();
-        let n = self.route.len();
-
-        if start >= n {
-            return solutions;
-        }
+    /// Serializa a rota como os rótulos das cidades separados por vírgula, no mesmo formato do
+    /// cabeçalho das instâncias em CSV.
+    pub fn to_labelled_csv<L: AsRef<str>>(&self, labels: &[L]) -> String {
+        self.route_labels(labels).join(",")
+    }
 
-        for target_pos in 0..n {
-            if target_pos == start {
-                continue;
-            }
+    /// Reconstrói uma solução serializada por [`Solution::to_labelled_csv`].
+    ///
+    /// Retorna `None` caso algum rótulo não pertença à instância.
+    pub fn from_labelled_csv<L: AsRef<str>, G: DistanceMatrix + ?Sized>(
+        csv: &str,
+        labels: &[L],
+        graph: &G,
+    ) -> Option<Self> {
+        let route = csv
+            .split(',')
+            .map(|label| labels.iter().position(|l| l.as_ref() == label.trim()))
+            .collect::<Option<Vec<_>>>()?;
+        let cost = Self::calculate_cost(&route, graph);
+        Some(Self { route, cost })
+    }
 
-            let mut new_route = self.route.clone();
-            let elem = new_route.remove(start);
-            new_route.insert(target_pos, elem);
+    fn neighbourhood_by_swap(&self, start: usize) -> impl Iterator<Item = Move> + use<N> {
+        let n = self.route.len();
 
-            let cost = Self::calculate_cost(&new_route, graph);
+        (0..n)
+            .filter(move |&j| start < n && j != start)
+            .map(move |j| Move::Swap(start, j))
+    }
 
-            solutions.push(Self {
-                route: new_route,
-                cost,
-            });
-        }
+    fn neighbourhood_by_shift(&self, start: usize) -> impl Iterator<Item = Move> + use<N> {
+        let n = self.route.len();
 
-        solutions
+        (0..n)
+            .filter(move |&to| start < n && to != start)
+            .map(move |to| Move::Shift { from: start, to })
     }
 
-    fn neighbourhood_by_or_opt(&self, graph: &[[f64; N]; N]) -> Vec<Self> {
+    /// Todos os pares de arestas não adjacentes da rota, incluindo a aresta que fecha o ciclo
+    /// (da última posição de volta à primeira).
+    fn neighbourhood_by_two_opt(&self) -> impl Iterator<Item = Move> + use<N> {
         let n = self.route.len();
-        let mut neighbours = Vec::new();
 
-        for seq_len in 1..=3.min(n) {
-            for i in 0..n {
-                if i + seq_len > n {
-                    break;
-                }
-
-                for target in 0..n {
-                    if target >= i && target < i + seq_len {
-                        continue;
-                    }
+        (0..n.saturating_sub(2)).flat_map(move |i| {
+            // Com `i = 0`, a aresta de fechamento `(n - 1, 0)` é adjacente a `(0, 1)`.
+            let last = if i == 0 { n - 1 } else { n };
+            (i + 2..last).map(move |j| Move::TwoOpt(i, j))
+        })
+    }
 
-                    let mut new_route = self.route.clone();
-                    let sequence: Vec<usize> = new_route.drain(i..(i + seq_len)).collect();
+    fn neighbourhood_by_or_opt(&self) -> impl Iterator<Item = Move> + use<N> {
+        let n = self.route.len();
 
-                    let insert_pos = if target > i { target - seq_len } else { target };
+        (1..=3.min(n)).flat_map(move |len| {
+            (0..=n - len).flat_map(move |start| {
+                (0..n)
+                    .filter(move |&target| target < start || target >= start + len)
+                    .map(move |target| Move::OrOpt {
+                        start,
+                        len,
+                        to: if target > start { target - len } else { target },
+                    })
+            })
+        })
+    }
 
-                    if insert_pos <= new_route.len() {
-                        new_route.splice(insert_pos..insert_pos, sequence);
+    /// Todos os trios de arestas `(i, i + 1)`, `(j, j + 1)` e `(k, k + 1)` da rota, com cada uma
+    /// das sete formas de reconectá-las.
+    fn neighbourhood_by_three_opt(&self) -> impl Iterator<Item = Move> + use<N> {
+        let n = self.route.len();
 
-                        let cost = Self::calculate_cost(&new_route, graph);
+        (0..n).flat_map(move |i| {
+            (i + 1..n).flat_map(move |j| {
+                (j + 1..n).flat_map(move |k| {
+                    Reconnection::ALL
+                        .into_iter()
+                        .map(move |case| Move::ThreeOpt { i, j, k, case })
+                })
+            })
+        })
+    }
 
-                        neighbours.push(Solution {
-                            route: new_route,
-                            cost,
-                        });
-                    }
+    /// Os movimentos do or-opt, reinserindo cada trecho de 2 ou 3 vértices também invertido.
+    fn neighbourhood_by_or_3opt(&self) -> impl Iterator<Item = Move> + use<N> {
+        self.neighbourhood_by_or_opt().flat_map(|m| {
+            let reversed = match m {
+                Move::OrOpt { start, len, to } if len > 1 => {
+                    Some(Move::OrOptReversed { start, len, to })
                 }
+                _ => None,
+            };
+            iter::once(m).chain(reversed)
+        })
+    }
+
+    /// Escolhe o movimento de `moves` com a maior redução de custo, caso algum melhore a rota.
+    fn best_move<G: DistanceMatrix + ?Sized>(
+        &self,
+        graph: &G,
+        moves: impl Iterator<Item = Move>,
+    ) -> Option<(Move, f64)> {
+        let mut best: Option<(Move, f64)> = None;
+
+        for m in moves {
+            let delta = m.delta(&self.route, graph);
+            if delta < -EPSILON && best.is_none_or(|(_, d)| delta < d) {
+                best = Some((m, delta));
             }
         }
 
-        neighbours
+        best
     }
-}
-
-impl<const N: usize> LocalSearch<[[f64; N]; N]> for Solution<N> {
-    fn swap(&self, graph: &[[f64; N]; N], start: usize) -> Self {
-        let mut best_solution: Solution<N> = self.clone();
-        let mut found_better_solution = true;
 
-        while found_better_solution {
-            found_better_solution = false;
-            let solutions = best_solution.neighbourhood_by_swap(graph, start);
+    /// Primeiro movimento de `moves` que melhora a rota, se houver.
+    fn first_move<G: DistanceMatrix + ?Sized>(
+        &self,
+        graph: &G,
+        moves: impl Iterator<Item = Move>,
+    ) -> Option<(Move, f64)> {
+        moves
+            .map(|m| (m, m.delta(&self.route, graph)))
+            .find(|&(_, delta)| delta < -EPSILON)
+    }
 
-            let mut tmp_solution = best_solution.clone();
+    /// Busca local genérica: aplica, segundo a `policy`, movimentos de melhora da vizinhança
+    /// gerada por `neighbourhood` até não haver mais melhora ou até atingir o limite de movimentos.
+    fn descend<G: DistanceMatrix + ?Sized, I: Iterator<Item = Move>>(
+        &self,
+        graph: &G,
+        policy: Policy,
+        neighbourhood: impl Fn(&Self) -> I,
+    ) -> Descent<Self> {
+        let mut solution = self.clone();
+        let mut moves = 0;
+
+        let local_optimum = loop {
+            if policy.max_moves.is_some_and(|max| moves >= max) {
+                break false;
+            }
 
-            for s in solutions.iter() {
-                if s.cost < tmp_solution.cost {
-                    tmp_solution = s.clone();
+            let found = match policy.pivoting {
+                Pivoting::FirstImprovement => solution.first_move(graph, neighbourhood(&solution)),
+                Pivoting::BestImprovement => solution.best_move(graph, neighbourhood(&solution)),
+            };
+            match found {
+                Some((m, delta)) => {
+                    solution.apply(m, delta);
+                    moves += 1;
                 }
+                None => break true,
             }
+        };
 
-            if tmp_solution.cost < best_solution.cost {
-                best_solution = tmp_solution;
-                found_better_solution = true;
-            }
+        solution.cost = Self::calculate_cost(&solution.route, graph);
+        Descent {
+            solution,
+            moves,
+            local_optimum,
         }
+    }
 
-        best_solution
+    /// Aplica `m` na rota, atualizando o custo pela variação `delta` já calculada.
+    fn apply(&mut self, m: Move, delta: f64) {
+        m.apply(&mut self.route);
+        self.cost += delta;
     }
+}
 
-    fn two_opt(&self, graph: &[[f64; N]; N]) -> Self {
-        let n = graph.len();
-        let mut current_solution: Solution<N> = self.clone();
+/// Variação mínima de custo para que um movimento seja considerado uma melhora. Evita que erros
+/// de arredondamento na avaliação incremental façam a busca ciclar entre rotas equivalentes.
+const EPSILON: f64 = 1e-9;
+
+/// Movimento de vizinhança sobre as posições de uma rota.
+///
+/// A variação de custo de cada movimento é avaliada em O(1) por [`Move::delta`], olhando apenas
+/// para as arestas removidas e inseridas, e somente o movimento escolhido é aplicado na rota por
+/// [`Move::apply`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    /// Troca os vértices das posições `i` e `j`.
+    Swap(usize, usize),
+    /// Remove o vértice da posição `from` e o reinsere na posição `to` da rota resultante.
+    Shift { from: usize, to: usize },
+    /// Inverte o trecho `route[i + 1..=j]`, trocando as arestas `(i, i + 1)` e `(j, j + 1)` por
+    /// `(i, j)` e `(i + 1, j + 1)`. Assume custos simétricos.
+    TwoOpt(usize, usize),
+    /// Remove o trecho `route[start..start + len]` e o reinsere na posição `to` da rota
+    /// resultante.
+    OrOpt { start: usize, len: usize, to: usize },
+    /// Como [`Move::OrOpt`], mas reinsere o trecho invertido. Assume custos simétricos.
+    OrOptReversed { start: usize, len: usize, to: usize },
+    /// Remove as arestas `(i, i + 1)`, `(j, j + 1)` e `(k, k + 1)`, com `i < j < k`, e reconecta
+    /// os trechos `route[i + 1..=j]` e `route[j + 1..=k]` segundo `case`. Assume custos simétricos.
+    ThreeOpt {
+        i: usize,
+        j: usize,
+        k: usize,
+        case: Reconnection,
+    },
+}
 
-        'outer: for i in 0..(n - 2) {
-            for j in i + 2..n {
-                let mut new_route: Vec<usize> = Vec::with_capacity(n);
+/// Formas de reconectar os trechos `A = route[i + 1..=j]` e `B = route[j + 1..=k]` em um
+/// movimento 3-opt. `A'` e `B'` denotam os trechos invertidos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reconnection {
+    /// `A' B`: equivale a um 2-opt sobre as arestas `i` e `j`.
+    ReverseFirst,
+    /// `A B'`: equivale a um 2-opt sobre as arestas `j` e `k`.
+    ReverseSecond,
+    /// `B' A'`: equivale a um 2-opt sobre as arestas `i` e `k`.
+    ReverseBoth,
+    /// `A' B'`.
+    ReverseEach,
+    /// `B A`: troca os trechos de lugar (*or-3opt* puro).
+    Exchange,
+    /// `B A'`.
+    ExchangeReverseFirst,
+    /// `B' A`.
+    ExchangeReverseSecond,
+}
 
-                new_route.extend_from_slice(&self.route[0..=i]);
-                new_route.extend(self.route[i + 1..=j].iter().rev());
+impl Reconnection {
+    pub const ALL: [Reconnection; 7] = [
+        Self::ReverseFirst,
+        Self::ReverseSecond,
+        Self::ReverseBoth,
+        Self::ReverseEach,
+        Self::Exchange,
+        Self::ExchangeReverseFirst,
+        Self::ExchangeReverseSecond,
+    ];
+}
 
-                if j + 1 < n {
-                    new_route.extend_from_slice(&self.route[j + 1..]);
-                }
+impl Move {
+    /// Variação no custo da rota caso o movimento seja aplicado.
+    pub fn delta<G: DistanceMatrix + ?Sized>(&self, route: &[usize], graph: &G) -> f64 {
+        let n = route.len();
+
+        // Em rotas muito pequenas as arestas removidas e inseridas se sobrepõem; como a avaliação
+        // completa é barata, ela é usada no lugar dos casos especiais.
+        if n < 5 {
+            let mut new_route = route.to_vec();
+            self.apply(&mut new_route);
+            return Solution::<0>::calculate_cost(&new_route, graph)
+                - Solution::<0>::calculate_cost(route, graph);
+        }
 
-                let new_cost = Self::calculate_cost(&new_route, graph);
-                if new_cost < current_solution.cost {
-                    current_solution = Solution {
-                        route: new_route,
-                        cost: new_cost,
-                    };
-                    break 'outer;
-                }
+        let d = |u: usize, v: usize| graph.dist(route[u], route[v]);
+        let prev = |i: usize| (i + n - 1) % n;
+        let next = |i: usize| (i + 1) % n;
+
+        match *self {
+            Move::Swap(i, j) if i == j => 0.,
+            Move::Swap(i, j) => {
+                let (i, j) = (i.min(j), i.max(j));
+                // Se as posições forem vizinhas no ciclo, a aresta entre elas apenas é invertida.
+                let (i, j) = match (next(i) == j, next(j) == i) {
+                    (true, _) => (i, j),
+                    (_, true) => (j, i),
+                    _ => {
+                        return d(prev(i), j) + d(j, next(i)) + d(prev(j), i) + d(i, next(j))
+                            - d(prev(i), i)
+                            - d(i, next(i))
+                            - d(prev(j), j)
+                            - d(j, next(j));
+                    }
+                };
+                d(prev(i), j) + d(j, i) + d(i, next(j)) - d(prev(i), i) - d(i, j) - d(j, next(j))
+            }
+            Move::Shift { from, to } => Move::OrOpt {
+                start: from,
+                len: 1,
+                to,
+            }
+            .delta(route, graph),
+            Move::TwoOpt(i, j) => d(i, j) + d(next(i), next(j)) - d(i, next(i)) - d(j, next(j)),
+            Move::OrOpt { start, len, to } | Move::OrOptReversed { start, len, to } => {
+                let (first, last) = (start, start + len - 1);
+                let (a, b) = (prev(first), next(last));
+
+                // Posição, na rota original, do k-ésimo vértice da rota sem o trecho.
+                let m = n - len;
+                let orig = |k: usize| if k < start { k } else { k + len };
+                let (x, y) = (orig((to + m - 1) % m), orig(to % m));
+
+                // O trecho invertido entra pelo último vértice e sai pelo primeiro.
+                let (head, tail) = match self {
+                    Move::OrOptReversed { .. } => (last, first),
+                    _ => (first, last),
+                };
+
+                d(a, b) + d(x, head) + d(tail, y) - d(a, first) - d(last, b) - d(x, y)
+            }
+            Move::ThreeOpt { i, j, k, case } => {
+                let (a, b, c, dd, e, f) = (i, i + 1, j, j + 1, k, next(k));
+                let removed = d(a, b) + d(c, dd) + d(e, f);
+                let added = match case {
+                    Reconnection::ReverseFirst => d(a, c) + d(b, dd) + d(e, f),
+                    Reconnection::ReverseSecond => d(a, b) + d(c, e) + d(dd, f),
+                    Reconnection::ReverseBoth => d(a, e) + d(dd, c) + d(b, f),
+                    Reconnection::ReverseEach => d(a, c) + d(b, e) + d(dd, f),
+                    Reconnection::Exchange => d(a, dd) + d(e, b) + d(c, f),
+                    Reconnection::ExchangeReverseFirst => d(a, dd) + d(e, c) + d(b, f),
+                    Reconnection::ExchangeReverseSecond => d(a, e) + d(dd, b) + d(c, f),
+                };
+                added - removed
             }
         }
-        current_solution
     }
 
-    fn shift(&self, graph: &[[f64; N]; N], start: usize) -> Self {
-        let mut best_solution: Solution<N> = self.clone();
-        let mut found_better_solution = true;
-
-        while found_better_solution {
-            found_better_solution = false;
-
-            let solutions = best_solution.neighbourhood_by_shift(graph, start);
-
-            let mut tmp_solution = best_solution.clone();
-
-            for s in solutions.iter() {
-                if s.cost < tmp_solution.cost {
-                    tmp_solution = s.clone();
-                }
+    /// Aplica o movimento na rota.
+    pub fn apply(&self, route: &mut Vec<usize>) {
+        match *self {
+            Move::Swap(i, j) => route.swap(i, j),
+            Move::Shift { from, to } => {
+                let v = route.remove(from);
+                route.insert(to, v);
             }
-
-            if tmp_solution.cost < best_solution.cost {
-                best_solution = tmp_solution;
-                found_better_solution = true;
+            Move::TwoOpt(i, j) => route[i + 1..=j].reverse(),
+            Move::OrOpt { start, len, to } => {
+                let sequence: Vec<usize> = route.drain(start..start + len).collect();
+                route.splice(to..to, sequence);
+            }
+            Move::OrOptReversed { start, len, to } => {
+                let sequence: Vec<usize> = route.drain(start..start + len).rev().collect();
+                route.splice(to..to, sequence);
+            }
+            Move::ThreeOpt { i, j, k, case } => {
+                let a = &route[i + 1..=j];
+                let b = &route[j + 1..=k];
+                let middle: Vec<usize> = match case {
+                    Reconnection::ReverseFirst => a.iter().rev().chain(b).copied().collect(),
+                    Reconnection::ReverseSecond => {
+                        a.iter().chain(b.iter().rev()).copied().collect()
+                    }
+                    Reconnection::ReverseBoth => {
+                        b.iter().rev().chain(a.iter().rev()).copied().collect()
+                    }
+                    Reconnection::ReverseEach => {
+                        a.iter().rev().chain(b.iter().rev()).copied().collect()
+                    }
+                    Reconnection::Exchange => b.iter().chain(a).copied().collect(),
+                    Reconnection::ExchangeReverseFirst => {
+                        b.iter().chain(a.iter().rev()).copied().collect()
+                    }
+                    Reconnection::ExchangeReverseSecond => {
+                        b.iter().rev().chain(a).copied().collect()
+                    }
+                };
+                route[i + 1..=k].copy_from_slice(&middle);
             }
         }
-        best_solution
     }
+}
 
-    fn or_opt(&self, graph: &[[f64; N]; N]) -> Self {
-        let mut best_solution: Solution<N> = self.clone();
-        let mut found_better_solution = true;
+impl<const N: usize, G: DistanceMatrix + ?Sized> LocalSearch<G> for Solution<N> {
+    fn swap_with(&self, graph: &G, start: usize, policy: Policy) -> Descent<Self> {
+        self.descend(graph, policy, |s| s.neighbourhood_by_swap(start))
+    }
 
-        while found_better_solution {
-            found_better_solution = false;
+    fn two_opt_with(&self, graph: &G, policy: Policy) -> Descent<Self> {
+        self.descend(graph, policy, |s| s.neighbourhood_by_two_opt())
+    }
 
-            let solutions = best_solution.neighbourhood_by_or_opt(graph);
+    fn shift_with(&self, graph: &G, start: usize, policy: Policy) -> Descent<Self> {
+        self.descend(graph, policy, |s| s.neighbourhood_by_shift(start))
+    }
 
-            let mut tmp_solution = best_solution.clone();
+    fn or_opt_with(&self, graph: &G, policy: Policy) -> Descent<Self> {
+        self.descend(graph, policy, |s| s.neighbourhood_by_or_opt())
+    }
 
-            for s in solutions.iter() {
-                if s.cost < tmp_solution.cost {
-                    tmp_solution = s.clone();
-                }
-            }
+    fn three_opt_with(&self, graph: &G, policy: Policy) -> Descent<Self> {
+        self.descend(graph, policy, |s| s.neighbourhood_by_three_opt())
+    }
 
-            if tmp_solution.cost < best_solution.cost {
-                best_solution = tmp_solution;
-                found_better_solution = true;
-            }
-        }
+    fn or_3opt_with(&self, graph: &G, policy: Policy) -> Descent<Self> {
+        self.descend(graph, policy, |s| s.neighbourhood_by_or_3opt())
+    }
+
+    fn lin_kernighan_with(&self, graph: &G, params: &LinKernighan) -> Descent<Self> {
+        params.improve(self, graph)
+    }
+
+    fn two_opt_with_candidates(&self, graph: &G, candidates: &CandidateList) -> Descent<Self> {
+        candidates::two_opt(self, graph, candidates)
+    }
+
+    fn or_opt_with_candidates(&self, graph: &G, candidates: &CandidateList) -> Descent<Self> {
+        candidates::or_opt(self, graph, candidates, 3)
+    }
 
-        best_solution
+    fn shift_with_candidates(&self, graph: &G, candidates: &CandidateList) -> Descent<Self> {
+        candidates::or_opt(self, graph, candidates, 1)
     }
 }
 
-/*
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,7 +694,7 @@ mod tests {
             vec![3.0, 5.0, 1.0, 6.0, INF],
         ];
 
-        let mut solution = Solution {
+        let mut solution = DynSolution {
             route: vec![0, 1, 2, 3, 4],
             cost: 25.0,
         };
@@ -252,7 +713,7 @@ mod tests {
             vec![3.0, 5.0, 1.0, 6.0, INF],
         ];
 
-        let mut solution = Solution {
+        let mut solution = DynSolution {
             route: vec![0, 1, 3, 4, 2],
             cost: 12.0,
         };
@@ -271,7 +732,7 @@ mod tests {
             vec![3.0, 5.0, 1.0, 6.0, INF],
         ];
 
-        let mut solution = Solution {
+        let mut solution = DynSolution {
             route: vec![0, 1, 2, 3, 4],
             cost: 25.0,
         };
@@ -290,7 +751,7 @@ mod tests {
             vec![3.0, 5.0, 1.0, 6.0, INF],
         ];
 
-        let mut solution = Solution {
+        let mut solution = DynSolution {
             route: vec![0, 1, 3, 4, 2],
             cost: 12.0,
         };
@@ -309,7 +770,7 @@ mod tests {
             vec![3.0, 5.0, 1.0, 6.0, INF],
         ];
 
-        let mut solution = Solution {
+        let mut solution = DynSolution {
             route: vec![0, 1, 2, 3, 4],
             cost: 25.0,
         };
@@ -328,7 +789,7 @@ mod tests {
             vec![3.0, 5.0, 1.0, 6.0, INF],
         ];
 
-        let mut solution = Solution {
+        let mut solution = DynSolution {
             route: vec![0, 1, 3, 4, 2],
             cost: 12.0,
         };
@@ -347,7 +808,7 @@ mod tests {
             vec![1.0, 9.0, 9.0, 1.0, INF],
         ];
 
-        let mut solution = Solution {
+        let mut solution = DynSolution {
             route: vec![0, 3, 2, 1, 4],
             cost: 21.0,
         };
@@ -367,16 +828,195 @@ mod tests {
             vec![2.0, 8.0, 8.0, 2.0, INF],
         ];
 
-        let mut solution = Solution {
+        let mut solution = DynSolution {
             route: vec![0, 2, 4, 1, 3],
             cost: 40.0,
         };
 
+        let first_move =
+            solution.two_opt_with(&graph, Policy::first_improvement().with_max_moves(1));
+        assert_eq!(first_move.solution.cost, 28.0);
+        assert_eq!(first_move.solution.route, [0, 4, 2, 1, 3]);
+        assert!(!first_move.local_optimum);
+
         solution = solution.two_opt(&graph);
+        assert_eq!(solution.cost, 10.0);
+        assert_eq!(solution.route, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn two_opt_test_3() {
+        // Rota que visita apenas parte dos vértices do grafo.
+        let graph = vec![
+            vec![INF, 1.0, 9.0, 9.0, 1.0, 5.0, 5.0],
+            vec![1.0, INF, 1.0, 9.0, 9.0, 5.0, 5.0],
+            vec![9.0, 1.0, INF, 1.0, 9.0, 5.0, 5.0],
+            vec![9.0, 9.0, 1.0, INF, 1.0, 5.0, 5.0],
+            vec![1.0, 9.0, 9.0, 1.0, INF, 5.0, 5.0],
+            vec![5.0, 5.0, 5.0, 5.0, 5.0, INF, 5.0],
+            vec![5.0, 5.0, 5.0, 5.0, 5.0, 5.0, INF],
+        ];
 
-        assert_eq!(solution.cost, 28.0);
-        assert_eq!(solution.route, [0, 4, 2, 1, 3]);
+        let solution = DynSolution {
+            route: vec![1, 0, 3, 2, 4],
+            cost: 29.0,
+        };
+
+        let d = solution.two_opt_with(&graph, Policy::best_improvement());
+        assert!(d.local_optimum);
+        assert_eq!(d.solution.cost, 5.0);
     }
-}
 
- */
\ No newline at end of file
+    #[test]
+    fn labels_test() {
+        let graph = vec![
+            vec![INF, 1.0, 2.0],
+            vec![1.0, INF, 3.0],
+            vec![2.0, 3.0, INF],
+        ];
+        let labels = ["1", "7", "8"];
+
+        let solution = DynSolution {
+            route: vec![0, 2, 1],
+            cost: 6.0,
+        };
+
+        assert_eq!(solution.display(&labels).to_string(), "1 -> 8 -> 7 -> 1");
+        assert_eq!(solution.to_labelled_csv(&labels), "1,8,7");
+
+        let parsed = DynSolution::from_labelled_csv("1,8,7", &labels, &graph).unwrap();
+        assert_eq!(parsed.route, solution.route);
+        assert_eq!(parsed.cost, 6.0);
+        assert!(DynSolution::from_labelled_csv("1,9,7", &labels, &graph).is_none());
+    }
+
+    #[test]
+    fn move_delta_test() {
+        use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for n in [4, 5, 6, 9] {
+            let w: Vec<Vec<f64>> = (0..n)
+                .map(|_| (0..n).map(|_| rng.random_range(1.0..100.0)).collect())
+                .collect();
+            let graph: Vec<Vec<f64>> = (0..n)
+                .map(|u| {
+                    (0..n)
+                        .map(|v| if u == v { INF } else { w[u.min(v)][u.max(v)] })
+                        .collect()
+                })
+                .collect();
+            let mut route: Vec<usize> = (0..n).collect();
+            route.shuffle(&mut rng);
+            let solution = DynSolution {
+                cost: DynSolution::calculate_cost(&route, &graph),
+                route,
+            };
+
+            let moves = (0..n)
+                .flat_map(|i| solution.neighbourhood_by_swap(i))
+                .chain((0..n).flat_map(|i| solution.neighbourhood_by_shift(i)))
+                .chain(solution.neighbourhood_by_or_3opt())
+                .chain(solution.neighbourhood_by_three_opt())
+                .chain((0..n).flat_map(|i| (i + 1..n).map(move |j| Move::TwoOpt(i, j))));
+
+            for m in moves {
+                let mut route = solution.route.clone();
+                m.apply(&mut route);
+                let expected = DynSolution::calculate_cost(&route, &graph) - solution.cost;
+
+                assert!(
+                    (m.delta(&solution.route, &graph) - expected).abs() < 1e-9,
+                    "{m:?} on {:?}",
+                    solution.route
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn policy_test() {
+        let graph = vec![
+            vec![INF, 1.0, 2.0, 4.0, 3.0],
+            vec![1.0, INF, 7.0, 2.0, 5.0],
+            vec![2.0, 7.0, INF, 8.0, 1.0],
+            vec![4.0, 2.0, 8.0, INF, 6.0],
+            vec![3.0, 5.0, 1.0, 6.0, INF],
+        ];
+
+        let solution = DynSolution {
+            route: vec![0, 1, 2, 3, 4],
+            cost: 25.0,
+        };
+
+        for policy in [Policy::first_improvement(), Policy::best_improvement()] {
+            let searches = [
+                solution.swap_with(&graph, 0, policy),
+                solution.shift_with(&graph, 2, policy),
+                solution.two_opt_with(&graph, policy),
+                solution.or_opt_with(&graph, policy),
+                solution.three_opt_with(&graph, policy),
+                solution.or_3opt_with(&graph, policy),
+            ];
+            for d in searches {
+                assert!(d.local_optimum);
+                assert!(d.moves > 0);
+                assert!(d.solution.cost < solution.cost);
+                assert_eq!(
+                    d.solution.cost,
+                    DynSolution::calculate_cost(&d.solution.route, &graph)
+                );
+            }
+
+            let bounded = solution.or_opt_with(&graph, policy.with_max_moves(1));
+            assert_eq!(bounded.moves, 1);
+            assert!(!bounded.local_optimum);
+        }
+    }
+
+    #[test]
+    fn three_opt_test_1() {
+        let graph = vec![
+            vec![INF, 1.0, 2.0, 4.0, 3.0],
+            vec![1.0, INF, 7.0, 2.0, 5.0],
+            vec![2.0, 7.0, INF, 8.0, 1.0],
+            vec![4.0, 2.0, 8.0, INF, 6.0],
+            vec![3.0, 5.0, 1.0, 6.0, INF],
+        ];
+
+        let mut solution = DynSolution {
+            route: vec![0, 1, 2, 3, 4],
+            cost: 25.0,
+        };
+
+        solution = solution.three_opt(&graph);
+        assert_eq!(solution.cost, 12.0);
+    }
+
+    #[test]
+    fn or_3opt_test_1() {
+        let graph = vec![
+            vec![INF, 1.0, 9.0, 9.0, 1.0],
+            vec![1.0, INF, 1.0, 9.0, 9.0],
+            vec![9.0, 1.0, INF, 1.0, 9.0],
+            vec![9.0, 9.0, 1.0, INF, 1.0],
+            vec![1.0, 9.0, 9.0, 1.0, INF],
+        ];
+
+        let mut solution = DynSolution {
+            route: vec![0, 3, 2, 1, 4],
+            cost: 21.0,
+        };
+
+        solution = solution.or_3opt(&graph);
+        assert_eq!(solution.cost, 5.0);
+    }
+
+    #[test]
+    fn neighbourhood_from_str_test() {
+        for n in Neighbourhood::ALL {
+            assert_eq!(n.to_string().parse(), Ok(n));
+        }
+        assert!("4opt".parse::<Neighbourhood>().is_err());
+    }
+}
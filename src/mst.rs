@@ -0,0 +1,130 @@
+//! Árvore geradora mínima (AGM) e construções derivadas dela.
+//!
+//! Como remover uma aresta de um ciclo hamiltoniano resulta em uma árvore geradora, o peso da AGM
+//! é um limitante inferior para o custo de qualquer rota (desde que os custos não sejam
+//! negativos). A AGM também é a base da heurística da árvore dupla, que custa no máximo o dobro
+//! do ótimo em instâncias métricas.
+
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{DynSolution, Solution};
+
+/// Árvore geradora de um grafo completo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanningTree {
+    /// Arestas `(pai, filho)`, na ordem em que foram adicionadas à árvore.
+    pub edges: Vec<(usize, usize)>,
+    /// Soma dos custos das arestas.
+    pub weight: f64,
+}
+
+impl SpanningTree {
+    /// Lista de adjacência da árvore.
+    pub fn adjacency(&self, node_count: usize) -> Vec<Vec<usize>> {
+        let mut adjacent = vec![Vec::new(); node_count];
+        for &(u, v) in &self.edges {
+            adjacent[u].push(v);
+            adjacent[v].push(u);
+        }
+        adjacent
+    }
+}
+
+/// Árvore geradora mínima pelo algoritmo de Prim, a partir do vértice 0, em O(n²). Adequado para
+/// matrizes de distâncias densas, onde todas as arestas existem.
+pub fn prim<G: DistanceMatrix + ?Sized>(graph: &G) -> SpanningTree {
+    let n = graph.node_count();
+    let mut in_tree = vec![false; n];
+    let mut best: Vec<(f64, usize)> = vec![(f64::INFINITY, 0); n];
+    let mut edges = Vec::with_capacity(n.saturating_sub(1));
+    let mut weight = 0.;
+
+    let mut current = 0;
+    for _ in 1..n {
+        in_tree[current] = true;
+        for v in (0..n).filter(|&v| !in_tree[v]) {
+            let d = graph.dist(current, v);
+            if d < best[v].0 {
+                best[v] = (d, current);
+            }
+        }
+        current = (0..n)
+            .filter(|&v| !in_tree[v])
+            .min_by(|&u, &v| best[u].0.total_cmp(&best[v].0))
+            .unwrap();
+        edges.push((best[current].1, current));
+        weight += best[current].0;
+    }
+
+    SpanningTree { edges, weight }
+}
+
+/// Limitante inferior para o custo de uma rota: o peso da AGM.
+pub fn lower_bound<G: DistanceMatrix + ?Sized>(graph: &G) -> f64 {
+    prim(graph).weight
+}
+
+/// Heurística da árvore dupla.
+///
+/// Percorrer cada aresta da AGM duas vezes gera um circuito euleriano; pular os vértices já
+/// visitados equivale a visitar os vértices na pré-ordem de uma busca em profundidade na árvore,
+/// a partir do vértice 0.
+pub fn double_tree<G: DistanceMatrix + ?Sized>(graph: &G) -> DynSolution {
+    let n = graph.node_count();
+    let adjacent = prim(graph).adjacency(n);
+
+    let mut route = Vec::with_capacity(n);
+    let mut visited = vec![false; n];
+    let mut stack: Vec<usize> = (n > 0).then_some(0).into_iter().collect();
+    while let Some(u) = stack.pop() {
+        if visited[u] {
+            continue;
+        }
+        visited[u] = true;
+        route.push(u);
+        stack.extend(adjacent[u].iter().rev().filter(|&&v| !visited[v]));
+    }
+
+    let cost = DynSolution::calculate_cost(&route, graph);
+    Solution { route, cost }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const INF: f64 = f64::INFINITY;
+
+    #[test]
+    fn prim_test() {
+        let graph = vec![
+            vec![INF, 10.0, 15.0, 20.0],
+            vec![10.0, INF, 35.0, 25.0],
+            vec![15.0, 35.0, INF, 30.0],
+            vec![20.0, 25.0, 30.0, INF],
+        ];
+
+        let tree = prim(&graph);
+
+        assert_eq!(tree.edges, [(0, 1), (0, 2), (0, 3)]);
+        assert_eq!(tree.weight, 45.0);
+        assert_eq!(lower_bound(&graph), 45.0);
+    }
+
+    #[test]
+    fn double_tree_test() {
+        // Estrela com centro 0 e folhas 1 e 2, e a folha 3 pendurada em 1.
+        let graph = vec![
+            vec![INF, 1.0, 1.0, 2.0],
+            vec![1.0, INF, 2.0, 1.0],
+            vec![1.0, 2.0, INF, 3.0],
+            vec![2.0, 1.0, 3.0, INF],
+        ];
+
+        let tree = prim(&graph);
+        assert_eq!(tree.weight, 3.0);
+
+        let solution = double_tree(&graph);
+        assert_eq!(solution.route, [0, 1, 3, 2]);
+        assert_eq!(solution.cost, 6.0);
+        assert!(solution.cost <= 2. * tree.weight);
+    }
+}
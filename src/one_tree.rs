@@ -0,0 +1,269 @@
+//! Limitante inferior de Held–Karp, pela 1-árvore com otimização por subgradiente.
+//!
+//! Uma 1-árvore é uma árvore geradora dos vértices `1..n` mais duas arestas ligando o vértice 0
+//! à árvore. Toda rota é uma 1-árvore, então o peso da 1-árvore mínima é um limitante inferior
+//! para o custo ótimo. Somar uma penalidade `π[v]` a todas as arestas de cada vértice `v` aumenta
+//! o custo de qualquer rota em exatamente `2 Σ π`, mas muda a 1-árvore mínima; assim,
+//! `L(π) = peso(1-árvore mínima com penalidades) - 2 Σ π` também é um limitante inferior. O
+//! limitante de Held–Karp é o máximo de `L(π)`, aproximado por subida de subgradiente: vértices
+//! com grau maior que 2 na 1-árvore recebem penalidades maiores, e vértices com grau 1, menores.
+//!
+//! Como as rotas podem ser percorridas em qualquer sentido, a aresta entre `u` e `v` custa
+//! `min(d(u, v), d(v, u))`.
+
+use crate::construction;
+use crate::graphs::DistanceMatrix;
+use crate::mst;
+
+/// 1-árvore com o vértice 0 como vértice especial.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OneTree {
+    pub edges: Vec<(usize, usize)>,
+    /// Grau de cada vértice na 1-árvore.
+    pub degree: Vec<usize>,
+    /// Soma dos custos das arestas, incluindo as penalidades.
+    pub weight: f64,
+}
+
+/// Parâmetros da subida de subgradiente.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Subgradient {
+    /// Quantidade máxima de iterações.
+    pub max_iterations: usize,
+    /// Custo de uma rota conhecida, usado no tamanho do passo. Se não for informado, usa o custo
+    /// da Inserção Mais Distante.
+    pub upper_bound: Option<f64>,
+    /// Fator inicial do passo.
+    pub initial_step: f64,
+    /// Quantidade de iterações sem melhora no limitante antes de reduzir o passo pela metade.
+    pub period: usize,
+    /// Fator do passo abaixo do qual a subida termina.
+    pub min_step: f64,
+}
+
+impl Default for Subgradient {
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            upper_bound: None,
+            initial_step: 2.,
+            period: 20,
+            min_step: 1e-4,
+        }
+    }
+}
+
+/// Resultado da subida de subgradiente.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LowerBound {
+    /// Maior limitante inferior encontrado.
+    pub value: f64,
+    /// Penalidades que resultaram em `value`.
+    pub penalties: Vec<f64>,
+    /// Quantidade de 1-árvores calculadas.
+    pub iterations: usize,
+    /// Se alguma 1-árvore era uma rota; nesse caso, `value` é o custo ótimo.
+    pub optimal: bool,
+}
+
+/// Custo de `u` até `v` com as penalidades `penalties`.
+fn cost<G: DistanceMatrix + ?Sized>(graph: &G, penalties: &[f64], u: usize, v: usize) -> f64 {
+    graph.dist(u, v).min(graph.dist(v, u)) + penalties[u] + penalties[v]
+}
+
+/// Os vértices `1..n` de `graph`, renumerados a partir de 0 e com as penalidades aplicadas.
+struct Penalised<'a, G: ?Sized> {
+    graph: &'a G,
+    penalties: &'a [f64],
+}
+
+impl<G: DistanceMatrix + ?Sized> DistanceMatrix for Penalised<'_, G> {
+    fn node_count(&self) -> usize {
+        self.graph.node_count() - 1
+    }
+
+    fn dist(&self, u: usize, v: usize) -> f64 {
+        cost(self.graph, self.penalties, u + 1, v + 1)
+    }
+}
+
+/// 1-árvore mínima de `graph` com as penalidades `penalties`. Requer ao menos 3 vértices.
+pub fn one_tree<G: DistanceMatrix + ?Sized>(graph: &G, penalties: &[f64]) -> OneTree {
+    let n = graph.node_count();
+    assert!(n >= 3, "a 1-tree needs at least 3 nodes");
+
+    let tree = mst::prim(&Penalised { graph, penalties });
+    let mut edges: Vec<(usize, usize)> = tree.edges.iter().map(|&(u, v)| (u + 1, v + 1)).collect();
+    let mut weight = tree.weight;
+
+    let mut nearest: Vec<usize> = (1..n).collect();
+    nearest.select_nth_unstable_by(1, |&u, &v| {
+        cost(graph, penalties, 0, u).total_cmp(&cost(graph, penalties, 0, v))
+    });
+    for &v in &nearest[..2] {
+        edges.push((0, v));
+        weight += cost(graph, penalties, 0, v);
+    }
+
+    let mut degree = vec![0; n];
+    for &(u, v) in &edges {
+        degree[u] += 1;
+        degree[v] += 1;
+    }
+    OneTree {
+        edges,
+        degree,
+        weight,
+    }
+}
+
+/// Limitante inferior de Held–Karp para `graph`, com os parâmetros padrão.
+pub fn lower_bound<G: DistanceMatrix + ?Sized>(graph: &G) -> LowerBound {
+    subgradient_bound(graph, &Subgradient::default())
+}
+
+/// Limitante inferior de Held–Karp para `graph`, por subida de subgradiente sobre as penalidades.
+pub fn subgradient_bound<G: DistanceMatrix + ?Sized>(
+    graph: &G,
+    params: &Subgradient,
+) -> LowerBound {
+    let n = graph.node_count();
+    if n < 3 {
+        // Só há uma rota possível.
+        let value = if n == 2 {
+            graph.dist(0, 1) + graph.dist(1, 0)
+        } else {
+            0.
+        };
+        return LowerBound {
+            value,
+            penalties: vec![0.; n],
+            iterations: 0,
+            optimal: true,
+        };
+    }
+
+    let upper_bound = params
+        .upper_bound
+        .unwrap_or_else(|| construction::farthest_insertion(graph, 0).cost);
+    let mut penalties = vec![0.; n];
+    let mut best = LowerBound {
+        value: f64::NEG_INFINITY,
+        penalties: penalties.clone(),
+        iterations: 0,
+        optimal: false,
+    };
+    let mut step = params.initial_step;
+    let mut stall = 0;
+
+    for iteration in 1..=params.max_iterations {
+        let tree = one_tree(graph, &penalties);
+        let value = tree.weight - 2. * penalties.iter().sum::<f64>();
+        best.iterations = iteration;
+
+        if value > best.value {
+            best.value = value;
+            best.penalties.clone_from(&penalties);
+            stall = 0;
+        } else {
+            stall += 1;
+            if stall >= params.period {
+                step /= 2.;
+                stall = 0;
+            }
+        }
+
+        let norm: f64 = tree.degree.iter().map(|&d| (d as f64 - 2.).powi(2)).sum();
+        if norm == 0. {
+            best.optimal = true;
+            break;
+        }
+        if step < params.min_step {
+            break;
+        }
+
+        let t = step * (upper_bound - value).max(0.) / norm;
+        for (p, &d) in penalties.iter_mut().zip(&tree.degree) {
+            *p += t * (d as f64 - 2.);
+        }
+    }
+
+    best
+}
+
+/// Distância relativa de `cost` até o limitante inferior `bound`: `(cost - bound) / bound`.
+/// Quando o limitante é atingido, erros de arredondamento nas penalidades podem deixá-lo
+/// ligeiramente acima do custo; nesse caso, o resultado é 0.
+pub fn gap(cost: f64, bound: f64) -> f64 {
+    ((cost - bound) / bound).max(0.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact;
+    use crate::graphs::Matrix;
+    use crate::local_search::DynSolution;
+    use std::f64::consts::PI;
+    const INF: f64 = f64::INFINITY;
+
+    #[test]
+    fn one_tree_test() {
+        let graph = vec![
+            vec![INF, 10.0, 15.0, 20.0],
+            vec![10.0, INF, 35.0, 25.0],
+            vec![15.0, 35.0, INF, 30.0],
+            vec![20.0, 25.0, 30.0, INF],
+        ];
+
+        let tree = one_tree(&graph, &[0.0; 4]);
+
+        // AGM de {1, 2, 3}: (1, 3) e (2, 3); mais (0, 1) e (0, 2).
+        assert_eq!(tree.weight, 80.0);
+        assert_eq!(tree.degree, [2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn subgradient_bound_test_1() {
+        // Vértices sobre um círculo: a 1-árvore com penalidades chega à rota ótima.
+        let n = 12;
+        let points: Vec<(f64, f64)> = (0..n)
+            .map(|i| {
+                let a = 2. * PI * i as f64 / n as f64;
+                (a.cos() * 10., a.sin() * 10.)
+            })
+            .collect();
+        let graph = Matrix::from_fn(n, |u, v| {
+            let (dx, dy) = (points[u].0 - points[v].0, points[u].1 - points[v].1);
+            (dx * dx + dy * dy).sqrt()
+        });
+        let optimum = DynSolution::calculate_cost(&(0..n).collect::<Vec<_>>(), &graph);
+
+        let bound = lower_bound(&graph);
+
+        assert!(bound.value <= optimum + 1e-6);
+        assert!(bound.value >= 0.99 * optimum);
+        assert!(bound.value >= mst::lower_bound(&graph));
+    }
+
+    #[test]
+    fn subgradient_bound_test_2() {
+        let graph = vec![
+            vec![INF, 2.0, 9.0, 10.0, 7.0],
+            vec![2.0, INF, 6.0, 4.0, 3.0],
+            vec![9.0, 6.0, INF, 8.0, 5.0],
+            vec![10.0, 4.0, 8.0, INF, 6.0],
+            vec![7.0, 3.0, 5.0, 6.0, INF],
+        ];
+
+        let optimum = exact::held_karp(&graph).unwrap();
+        assert_eq!(optimum.cost, 26.0);
+
+        let bound = lower_bound(&graph);
+
+        assert!(bound.value <= optimum.cost + 1e-6);
+        assert!(bound.value > mst::lower_bound(&graph));
+        assert!((gap(26.0, bound.value) - (26.0 - bound.value) / bound.value).abs() < 1e-12);
+        assert_eq!(gap(26.0, 26.0 + 1e-12), 0.0);
+    }
+}
@@ -0,0 +1,531 @@
+//! Busca tabu.
+//!
+//! A cada iteração, a busca avalia toda a vizinhança swap ou 2-opt da rota atual e aplica o
+//! melhor movimento admissível, mesmo que ele piore a rota. Para evitar que a busca volte às
+//! rotas recentes, os atributos desfeitos por cada movimento ficam proibidos (*tabu*) durante
+//! algumas iterações (o *tenure*):
+//! - com atributos de arestas, as arestas removidas não podem ser reinseridas;
+//! - com atributos de posições, os vértices movidos não podem voltar às posições que deixaram.
+//!
+//! Pelo critério de aspiração, um movimento tabu é admissível quando resulta em uma rota melhor
+//! que a melhor conhecida. Opcionalmente, uma memória de longo prazo conta quantas vezes cada
+//! atributo foi inserido, e os movimentos que não melhoram a rota são penalizados pela frequência
+//! dos seus atributos, diversificando a busca.
+//!
+//! Os atributos de arestas e o 2-opt assumem custos simétricos.
+
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{Move, Solution};
+
+/// Melhora mínima para que uma rota substitua a melhor conhecida.
+const EPSILON: f64 = 1e-9;
+
+/// Vizinhança explorada pela busca tabu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Neighbourhood {
+    Swap,
+    #[default]
+    TwoOpt,
+}
+
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "swap" => Ok(Self::Swap),
+            "2opt" => Ok(Self::TwoOpt),
+            _ => Err(format!(
+                "unsupported tabu neighbourhood `{s}`, expected `swap` or `2opt`"
+            )),
+        }
+    }
+}
+
+/// Atributo guardado na lista tabu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Attribute {
+    /// Arestas removidas.
+    #[default]
+    Edges,
+    /// Pares (vértice, posição) desfeitos.
+    Positions,
+}
+
+impl FromStr for Attribute {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "edges" => Ok(Self::Edges),
+            "positions" => Ok(Self::Positions),
+            _ => Err(format!(
+                "unknown tabu attribute `{s}`, expected `edges` or `positions`"
+            )),
+        }
+    }
+}
+
+/// Quantidade de iterações em que um atributo permanece tabu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tenure {
+    Fixed(usize),
+    /// Sorteado em `min..=max` a cada movimento.
+    Random {
+        min: usize,
+        max: usize,
+    },
+    /// Busca tabu reativa: o *tenure* começa em `min` e cresce 20% (mais 1) sempre que uma rota
+    /// já visitada se repete, até `max`. Após `2 × tenure` iterações sem repetições, decresce
+    /// 10%, até `min`.
+    Reactive {
+        min: usize,
+        max: usize,
+    },
+}
+
+impl FromStr for Tenure {
+    type Err = String;
+
+    /// `7` é um *tenure* fixo, `5-10` é sorteado e `reactive` é reativo entre 5 e 50.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid tenure `{s}`, expected `N`, `MIN-MAX` or `reactive`");
+        let parse = |t: &str| t.parse().map_err(|_| error());
+        match s.split_once('-') {
+            _ if s == "reactive" => Ok(Self::Reactive { min: 5, max: 50 }),
+            Some((min, max)) => match (parse(min)?, parse(max)?) {
+                (min, max) if min <= max => Ok(Self::Random { min, max }),
+                _ => Err(error()),
+            },
+            None => parse(s).map(Self::Fixed),
+        }
+    }
+}
+
+/// Configuração da busca tabu.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tabu {
+    pub neighbourhood: Neighbourhood,
+    pub attribute: Attribute,
+    pub tenure: Tenure,
+    /// Peso da penalidade por frequência da memória de longo prazo; `None` desativa a
+    /// diversificação. A penalidade de um movimento que não melhora a rota é
+    /// `peso × (inserções dos seus atributos / iterações) × custo médio de uma aresta`.
+    pub diversification: Option<f64>,
+    /// Quantidade máxima de iterações.
+    pub max_iterations: Option<usize>,
+    /// Tempo máximo de execução.
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for Tabu {
+    fn default() -> Self {
+        Self {
+            neighbourhood: Neighbourhood::TwoOpt,
+            attribute: Attribute::default(),
+            tenure: Tenure::Fixed(10),
+            diversification: None,
+            max_iterations: Some(1000),
+            time_limit: None,
+        }
+    }
+}
+
+/// Resultado da busca tabu.
+#[derive(Clone)]
+pub struct TabuResult<S> {
+    /// Melhor rota encontrada.
+    pub solution: S,
+    /// Quantidade de iterações executadas.
+    pub iterations: usize,
+    /// Iteração em que a melhor rota foi encontrada; 0 se for a rota inicial.
+    pub best_iteration: usize,
+    /// Movimentos tabu aceitos pelo critério de aspiração.
+    pub aspirations: usize,
+}
+
+/// Atributos inseridos e desfeitos por um movimento, como índices `u * n + v` de uma matriz
+/// `n × n`.
+struct Attributes {
+    added: [usize; 4],
+    removed: [usize; 4],
+    len: usize,
+}
+
+impl Attributes {
+    fn added(&self) -> &[usize] {
+        &self.added[..self.len]
+    }
+
+    fn removed(&self) -> &[usize] {
+        &self.removed[..self.len]
+    }
+}
+
+impl Tabu {
+    /// Atributos de `m` sobre `route`.
+    fn attributes(&self, route: &[usize], m: Move) -> Attributes {
+        let n = route.len();
+        let prev = |i: usize| route[(i + n - 1) % n];
+        let next = |i: usize| route[(i + 1) % n];
+        let edge = |u: usize, v: usize| u.min(v) * n + u.max(v);
+        let place = |v: usize, i: usize| v * n + i;
+
+        let (added, removed, len) = match (self.attribute, m) {
+            (Attribute::Positions, Move::Swap(i, j)) => (
+                [place(route[j], i), place(route[i], j), 0, 0],
+                [place(route[i], i), place(route[j], j), 0, 0],
+                2,
+            ),
+            // Os extremos do trecho invertido trocam de posição.
+            (Attribute::Positions, Move::TwoOpt(i, j)) => (
+                [place(route[j], i + 1), place(route[i + 1], j), 0, 0],
+                [place(route[i + 1], i + 1), place(route[j], j), 0, 0],
+                2,
+            ),
+            (Attribute::Edges, Move::TwoOpt(i, j)) => (
+                [edge(route[i], route[j]), edge(route[i + 1], next(j)), 0, 0],
+                [edge(route[i], route[i + 1]), edge(route[j], next(j)), 0, 0],
+                2,
+            ),
+            (Attribute::Edges, Move::Swap(i, j)) => {
+                let (a, b) = (route[i], route[j]);
+                // Se as posições forem vizinhas no ciclo, a aresta entre elas permanece.
+                if next(i) == b || next(j) == a {
+                    let (i, j) = if next(i) == b { (i, j) } else { (j, i) };
+                    let (a, b) = (route[i], route[j]);
+                    (
+                        [edge(prev(i), b), edge(a, next(j)), 0, 0],
+                        [edge(prev(i), a), edge(b, next(j)), 0, 0],
+                        2,
+                    )
+                } else {
+                    (
+                        [
+                            edge(prev(i), b),
+                            edge(b, next(i)),
+                            edge(prev(j), a),
+                            edge(a, next(j)),
+                        ],
+                        [
+                            edge(prev(i), a),
+                            edge(a, next(i)),
+                            edge(prev(j), b),
+                            edge(b, next(j)),
+                        ],
+                        4,
+                    )
+                }
+            }
+            _ => unreachable!("tabu moves are swaps or 2-opt moves"),
+        };
+        Attributes {
+            added,
+            removed,
+            len,
+        }
+    }
+
+    /// Todos os movimentos da vizinhança em uma rota com `n` vértices.
+    fn moves(&self, n: usize) -> Vec<Move> {
+        match self.neighbourhood {
+            Neighbourhood::Swap => (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| Move::Swap(i, j)))
+                .collect(),
+            // Com `i = 0`, a aresta de fechamento `(n - 1, 0)` é adjacente a `(0, 1)`.
+            Neighbourhood::TwoOpt => (0..n.saturating_sub(2))
+                .flat_map(|i| {
+                    let last = if i == 0 { n - 1 } else { n };
+                    (i + 2..last).map(move |j| Move::TwoOpt(i, j))
+                })
+                .collect(),
+        }
+    }
+
+    /// Executa a busca tabu a partir de `solution`.
+    pub fn run<const N: usize, G: DistanceMatrix, R: Rng + ?Sized>(
+        &self,
+        solution: &Solution<N>,
+        graph: &G,
+        rng: &mut R,
+    ) -> TabuResult<Solution<N>> {
+        assert!(
+            self.max_iterations.is_some() || self.time_limit.is_some(),
+            "tabu search needs an iteration or time budget"
+        );
+        if let Tenure::Random { min, max } | Tenure::Reactive { min, max } = self.tenure {
+            assert!(min <= max, "tenure range {min}-{max} is empty");
+        }
+        let now = Instant::now();
+        let n = solution.route.len();
+        let mut current = Solution {
+            route: solution.route.clone(),
+            cost: Solution::<N>::calculate_cost(&solution.route, graph),
+        };
+        let mut best = current.clone();
+        let mut result = TabuResult {
+            solution: current.clone(),
+            iterations: 0,
+            best_iteration: 0,
+            aspirations: 0,
+        };
+        // Com menos de 4 vértices, nenhum swap ou 2-opt muda o ciclo.
+        if n < 4 {
+            return result;
+        }
+
+        let moves = self.moves(n);
+        // Iteração até a qual cada atributo é tabu, e quantas vezes cada um foi inserido.
+        let mut tabu_until = vec![0; n * n];
+        let mut frequency = vec![0usize; n * n];
+        let mut tenure = match self.tenure {
+            Tenure::Fixed(t) => t,
+            Tenure::Random { min, .. } | Tenure::Reactive { min, .. } => min,
+        };
+        // Rotas já visitadas, guardadas apenas pelo hash de 64 bits da representação canônica.
+        let mut visited: HashSet<u64> = HashSet::new();
+        let mut last_change = 0;
+
+        let mut iteration = 0;
+        while self.max_iterations.is_none_or(|max| iteration < max)
+            && self.time_limit.is_none_or(|limit| now.elapsed() < limit)
+        {
+            iteration += 1;
+            let scale = current.cost / n as f64;
+
+            // Melhor movimento admissível, com a variação de custo penalizada e a real.
+            let mut chosen: Option<(Move, f64, f64, bool)> = None;
+            for &m in &moves {
+                let delta = m.delta(&current.route, graph);
+                let attributes = self.attributes(&current.route, m);
+                let tabu = attributes
+                    .added()
+                    .iter()
+                    .any(|&a| tabu_until[a] >= iteration);
+                let aspirated = current.cost + delta < best.cost - EPSILON;
+                if tabu && !aspirated {
+                    continue;
+                }
+                let penalised = match self.diversification {
+                    Some(weight) if delta >= 0. => {
+                        let count: usize = attributes.added().iter().map(|&a| frequency[a]).sum();
+                        delta + weight * count as f64 / iteration as f64 * scale
+                    }
+                    _ => delta,
+                };
+                if chosen.is_none_or(|(_, p, _, _)| penalised < p) {
+                    chosen = Some((m, penalised, delta, tabu));
+                }
+            }
+            // Todos os movimentos são tabu: a busca fica parada até que algum expire.
+            let Some((m, _, delta, tabu)) = chosen else {
+                continue;
+            };
+
+            let attributes = self.attributes(&current.route, m);
+            let t = match self.tenure {
+                Tenure::Random { min, max } => rng.random_range(min..=max),
+                _ => tenure,
+            };
+            for &a in attributes.removed() {
+                tabu_until[a] = iteration + t;
+            }
+            for &a in attributes.added() {
+                frequency[a] += 1;
+            }
+            m.apply(&mut current.route);
+            current.cost += delta;
+            if tabu {
+                result.aspirations += 1;
+            }
+            if current.cost < best.cost - EPSILON {
+                best.clone_from(&current);
+                result.best_iteration = iteration;
+            }
+
+            if let Tenure::Reactive { min, max } = self.tenure {
+                if !visited.insert(fingerprint(&current.route)) {
+                    tenure = (tenure + tenure / 5 + 1).min(max);
+                    last_change = iteration;
+                } else if iteration - last_change > 2 * tenure {
+                    tenure = (tenure - tenure / 10).max(min);
+                    last_change = iteration;
+                }
+            }
+        }
+
+        // Recalcula o custo para descartar os erros de arredondamento acumulados nos `delta`.
+        best.cost = Solution::<N>::calculate_cost(&best.route, graph);
+        TabuResult {
+            solution: best,
+            iterations: iteration,
+            ..result
+        }
+    }
+}
+
+/// Representação única de um ciclo: começa pelo vértice 0, no sentido em que o segundo vértice é
+/// menor que o último.
+fn canonical(route: &[usize]) -> Vec<usize> {
+    let mut route = route.to_vec();
+    if let Some(i) = route.iter().position(|&v| v == 0) {
+        route.rotate_left(i);
+    }
+    if route.len() > 2 && route[1] > route[route.len() - 1] {
+        route[1..].reverse();
+    }
+    route
+}
+
+/// Hash de 64 bits da representação canônica de `route`. Colisões só fazem o *tenure* crescer
+/// mais cedo, sem afetar a validade da busca.
+fn fingerprint(route: &[usize]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    canonical(route).hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact;
+    use crate::local_search::{DynSolution, LocalSearch};
+    use crate::test_util::euclidean;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn attributes_test() {
+        let tabu = Tabu::default();
+        let route = [0, 1, 2, 3, 4, 5];
+        let edge = |u: usize, v: usize| u.min(v) * 6 + u.max(v);
+
+        // As arestas retornadas são as que a avaliação completa da rota insere e remove.
+        let attributes = tabu.attributes(&route, Move::TwoOpt(1, 4));
+        assert_eq!(attributes.added(), [edge(1, 4), edge(2, 5)]);
+        assert_eq!(attributes.removed(), [edge(1, 2), edge(4, 5)]);
+
+        let tabu = Tabu {
+            neighbourhood: Neighbourhood::Swap,
+            ..tabu
+        };
+        let attributes = tabu.attributes(&route, Move::Swap(0, 5));
+        assert_eq!(attributes.added(), [edge(4, 0), edge(5, 1)]);
+        assert_eq!(attributes.removed(), [edge(4, 5), edge(0, 1)]);
+        let attributes = tabu.attributes(&route, Move::Swap(1, 3));
+        assert_eq!(
+            attributes.added(),
+            [edge(0, 3), edge(3, 2), edge(2, 1), edge(1, 4)]
+        );
+
+        assert_eq!(canonical(&[2, 1, 0, 3]), [0, 1, 2, 3]);
+        assert_eq!(canonical(&[3, 0, 1, 2]), [0, 1, 2, 3]);
+        assert_eq!(fingerprint(&[2, 1, 0, 3]), fingerprint(&[1, 2, 3, 0]));
+        assert_ne!(fingerprint(&[0, 1, 2, 3]), fingerprint(&[0, 2, 1, 3]));
+    }
+
+    #[test]
+    fn from_str_test() {
+        assert_eq!("swap".parse(), Ok(Neighbourhood::Swap));
+        assert_eq!(
+            "oropt".parse::<Neighbourhood>(),
+            Err("unsupported tabu neighbourhood `oropt`, expected `swap` or `2opt`".to_string())
+        );
+        assert_eq!("7".parse(), Ok(Tenure::Fixed(7)));
+        assert_eq!("5-5".parse(), Ok(Tenure::Random { min: 5, max: 5 }));
+        assert_eq!(
+            "10-5".parse::<Tenure>(),
+            Err("invalid tenure `10-5`, expected `N`, `MIN-MAX` or `reactive`".to_string())
+        );
+        assert!("5-x".parse::<Tenure>().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "tenure range 10-5 is empty")]
+    fn tenure_test_empty_range() {
+        let tabu = Tabu {
+            tenure: Tenure::Reactive { min: 10, max: 5 },
+            ..Tabu::default()
+        };
+        let solution = DynSolution {
+            route: (0..6).collect(),
+            cost: 0.,
+        };
+        tabu.run(&solution, &euclidean(6, 1), &mut StdRng::seed_from_u64(1));
+    }
+
+    #[test]
+    fn tabu_test_1() {
+        let graph = euclidean(10, 1);
+        let optimum = exact::held_karp(&graph).unwrap().cost;
+        let start = DynSolution {
+            route: (0..10).collect(),
+            cost: 0.,
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for (neighbourhood, attribute, tenure) in [
+            (Neighbourhood::TwoOpt, Attribute::Edges, Tenure::Fixed(5)),
+            (
+                Neighbourhood::TwoOpt,
+                Attribute::Positions,
+                "3-6".parse().unwrap(),
+            ),
+            (
+                Neighbourhood::Swap,
+                Attribute::Edges,
+                "reactive".parse().unwrap(),
+            ),
+        ] {
+            let tabu = Tabu {
+                neighbourhood,
+                attribute,
+                tenure,
+                max_iterations: Some(200),
+                ..Tabu::default()
+            };
+            let result = tabu.run(&start, &graph, &mut rng);
+
+            assert_eq!(result.iterations, 200);
+            assert_eq!(
+                result.solution.cost,
+                DynSolution::calculate_cost(&result.solution.route, &graph)
+            );
+            assert!(result.solution.cost >= optimum - 1e-9);
+            // A busca tabu passa pelo ótimo local do 2-opt e continua a partir dele.
+            assert!(result.solution.cost <= start.two_opt(&graph).cost + 1e-9);
+        }
+    }
+
+    #[test]
+    fn tabu_test_2() {
+        let graph = euclidean(25, 2);
+        let start = DynSolution {
+            route: (0..25).collect(),
+            cost: 0.,
+        };
+        let mut rng = StdRng::seed_from_u64(2);
+
+        let plain = Tabu {
+            max_iterations: Some(300),
+            ..Tabu::default()
+        };
+        let result = plain.run(&start, &graph, &mut rng);
+        assert!(result.best_iteration > 0);
+
+        let diversified = Tabu {
+            diversification: Some(1.),
+            time_limit: Some(Duration::from_secs(60)),
+            ..plain
+        }
+        .run(&start, &graph, &mut rng);
+        assert_eq!(diversified.iterations, 300);
+        assert!(diversified.solution.cost <= DynSolution::calculate_cost(&start.route, &graph));
+    }
+}
@@ -0,0 +1,24 @@
+//! Utilitários compartilhados pelos testes dos módulos.
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::graphs::Matrix;
+
+/// Matriz de distâncias euclidianas entre `n` pontos sorteados no quadrado `[0, 100)²`.
+pub(crate) fn euclidean(n: usize, seed: u64) -> Matrix {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let points: Vec<(f64, f64)> = (0..n)
+        .map(|_| (rng.random_range(0.0..100.0), rng.random_range(0.0..100.0)))
+        .collect();
+    Matrix::from_fn(n, |u, v| {
+        let (dx, dy) = (points[u].0 - points[v].0, points[u].1 - points[v].1);
+        (dx * dx + dy * dy).sqrt()
+    })
+}
+
+/// Verifica se `route` é uma permutação de `0..route.len()`.
+pub(crate) fn is_permutation(route: &[usize]) -> bool {
+    let mut sorted = route.to_vec();
+    sorted.sort();
+    sorted == (0..route.len()).collect::<Vec<_>>()
+}
@@ -0,0 +1,351 @@
+//! Leitura de instâncias no formato TSPLIB95.
+//!
+//! São suportadas as instâncias simétricas (`TYPE: TSP`) e assimétricas (`TYPE: ATSP`) cujos
+//! custos são dados explicitamente (`EDGE_WEIGHT_TYPE: EXPLICIT`, nos formatos `FULL_MATRIX`,
+//! `UPPER_ROW`, `UPPER_DIAG_ROW`, `LOWER_ROW` e `LOWER_DIAG_ROW`) ou calculados a partir das
+//! coordenadas dos vértices (`EUC_2D`, `CEIL_2D`, `ATT` e `GEO`). As distâncias seguem exatamente
+//! as funções da especificação da TSPLIB, de forma que os custos ótimos publicados (como 7542 para
+//! a `berlin52` e 10628 para a `att48`) continuam válidos.
+
+use std::{fs, io, path::Path, str::FromStr};
+
+use crate::graphs::Matrix;
+use crate::instance::invalid_data;
+
+/// Instância lida de um arquivo TSPLIB.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tsplib {
+    /// Nome da instância (campo `NAME`).
+    pub name: String,
+    /// Matriz de distâncias entre os vértices.
+    pub matrix: Matrix,
+    /// Coordenadas dos vértices, quando a instância é baseada em coordenadas.
+    pub coords: Option<Vec<(f64, f64)>>,
+}
+
+/// Forma como os custos das arestas são obtidos (campo `EDGE_WEIGHT_TYPE`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeWeightType {
+    Explicit,
+    Euc2d,
+    Ceil2d,
+    Att,
+    Geo,
+}
+
+/// Disposição dos custos em `EDGE_WEIGHT_SECTION` (campo `EDGE_WEIGHT_FORMAT`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeWeightFormat {
+    FullMatrix,
+    UpperRow,
+    UpperDiagRow,
+    LowerRow,
+    LowerDiagRow,
+}
+
+impl FromStr for EdgeWeightType {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        match s {
+            "EXPLICIT" => Ok(Self::Explicit),
+            "EUC_2D" => Ok(Self::Euc2d),
+            "CEIL_2D" => Ok(Self::Ceil2d),
+            "ATT" => Ok(Self::Att),
+            "GEO" => Ok(Self::Geo),
+            _ => Err(invalid_data(format!("unsupported EDGE_WEIGHT_TYPE `{s}`"))),
+        }
+    }
+}
+
+impl FromStr for EdgeWeightFormat {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        match s {
+            "FULL_MATRIX" => Ok(Self::FullMatrix),
+            "UPPER_ROW" => Ok(Self::UpperRow),
+            "UPPER_DIAG_ROW" => Ok(Self::UpperDiagRow),
+            "LOWER_ROW" => Ok(Self::LowerRow),
+            "LOWER_DIAG_ROW" => Ok(Self::LowerDiagRow),
+            _ => Err(invalid_data(format!(
+                "unsupported EDGE_WEIGHT_FORMAT `{s}`"
+            ))),
+        }
+    }
+}
+
+/// Lê uma instância TSPLIB do caminho `path`.
+pub fn read(path: impl AsRef<Path>) -> io::Result<Tsplib> {
+    parse(&fs::read_to_string(path)?)
+}
+
+/// Interpreta o conteúdo de uma instância TSPLIB.
+///
+/// # Erros
+/// Retorna um erro do tipo [`io::ErrorKind::InvalidData`] caso falte algum campo obrigatório
+/// (`DIMENSION`, `EDGE_WEIGHT_TYPE` ou a seção de dados correspondente), caso algum valor não seja
+/// numérico ou caso a quantidade de valores não corresponda à dimensão declarada.
+pub fn parse(content: &str) -> io::Result<Tsplib> {
+    let mut name = String::new();
+    let mut dimension: Option<usize> = None;
+    let mut weight_type: Option<EdgeWeightType> = None;
+    let mut weight_format = EdgeWeightFormat::FullMatrix;
+    let mut coords: Option<Vec<(f64, f64)>> = None;
+    let mut weights: Option<Vec<f64>> = None;
+
+    let mut lines = content.lines().map(str::trim).filter(|l| !l.is_empty());
+    while let Some(line) = lines.next() {
+        let (key, value) = match line.split_once(':') {
+            Some((k, v)) => (k.trim(), v.trim()),
+            None => (line, ""),
+        };
+
+        match key {
+            "NAME" => name = value.to_string(),
+            "TYPE" if value != "TSP" && value != "ATSP" => {
+                return Err(invalid_data(format!("unsupported TYPE `{value}`")));
+            }
+            "DIMENSION" => {
+                dimension =
+                    Some(value.parse().map_err(|_| {
+                        invalid_data(format!("DIMENSION `{value}` is not a number"))
+                    })?)
+            }
+            "EDGE_WEIGHT_TYPE" => weight_type = Some(value.parse()?),
+            "EDGE_WEIGHT_FORMAT" => weight_format = value.parse()?,
+            "NODE_COORD_SECTION" => {
+                let n = dimension.ok_or_else(|| section_before_dimension(key))?;
+                let mut c = Vec::with_capacity(n);
+                for line in lines.by_ref().take(n) {
+                    let values = numbers(line.split_whitespace(), key)?;
+                    let [_, x, y] = values[..] else {
+                        return Err(invalid_data(format!("{key}: malformed line `{line}`")));
+                    };
+                    c.push((x, y));
+                }
+                coords = Some(c);
+            }
+            "EDGE_WEIGHT_SECTION" => {
+                let n = dimension.ok_or_else(|| section_before_dimension(key))?;
+                let expected = match weight_format {
+                    EdgeWeightFormat::FullMatrix => n * n,
+                    EdgeWeightFormat::UpperRow | EdgeWeightFormat::LowerRow => {
+                        n * n.saturating_sub(1) / 2
+                    }
+                    EdgeWeightFormat::UpperDiagRow | EdgeWeightFormat::LowerDiagRow => {
+                        n * (n + 1) / 2
+                    }
+                };
+                let mut w = Vec::with_capacity(expected);
+                while w.len() < expected {
+                    let line = lines.next().ok_or_else(|| {
+                        invalid_data(format!("{key}: expected {expected} values"))
+                    })?;
+                    w.extend(numbers(line.split_whitespace(), key)?);
+                }
+                if w.len() != expected {
+                    return Err(invalid_data(format!(
+                        "{key}: expected {expected} values, found {}",
+                        w.len()
+                    )));
+                }
+                weights = Some(w);
+            }
+            "EOF" => break,
+            _ => {}
+        }
+    }
+
+    let n = dimension.ok_or_else(|| invalid_data("missing DIMENSION".to_string()))?;
+    let matrix = match weight_type {
+        Some(EdgeWeightType::Explicit) => {
+            let w =
+                weights.ok_or_else(|| invalid_data("missing EDGE_WEIGHT_SECTION".to_string()))?;
+            explicit_matrix(n, weight_format, &w)
+        }
+        Some(kind) => {
+            let c = coords
+                .as_ref()
+                .ok_or_else(|| invalid_data("missing NODE_COORD_SECTION".to_string()))?;
+            if c.len() != n {
+                return Err(invalid_data(format!(
+                    "NODE_COORD_SECTION has {} nodes, expected {n}",
+                    c.len()
+                )));
+            }
+            Matrix::from_fn(n, |i, j| {
+                if i == j {
+                    0.
+                } else {
+                    distance(kind, c[i], c[j])
+                }
+            })
+        }
+        None => return Err(invalid_data("missing EDGE_WEIGHT_TYPE".to_string())),
+    };
+
+    Ok(Tsplib {
+        name,
+        matrix,
+        coords,
+    })
+}
+
+/// Distância entre dois pontos segundo a função `kind` da TSPLIB.
+///
+/// # Panics
+/// Caso `kind` seja [`EdgeWeightType::Explicit`], que não possui função de distância.
+pub fn distance(kind: EdgeWeightType, (xi, yi): (f64, f64), (xj, yj): (f64, f64)) -> f64 {
+    let (dx, dy) = (xi - xj, yi - yj);
+    match kind {
+        EdgeWeightType::Euc2d => nint((dx * dx + dy * dy).sqrt()),
+        EdgeWeightType::Ceil2d => (dx * dx + dy * dy).sqrt().ceil(),
+        EdgeWeightType::Att => {
+            let r = ((dx * dx + dy * dy) / 10.).sqrt();
+            let t = nint(r);
+            if t < r { t + 1. } else { t }
+        }
+        EdgeWeightType::Geo => {
+            const RRR: f64 = 6378.388;
+            let (lat_i, lon_i) = (geo_radians(xi), geo_radians(yi));
+            let (lat_j, lon_j) = (geo_radians(xj), geo_radians(yj));
+            let q1 = (lon_i - lon_j).cos();
+            let q2 = (lat_i - lat_j).cos();
+            let q3 = (lat_i + lat_j).cos();
+            (RRR * (0.5 * ((1. + q1) * q2 - (1. - q1) * q3)).acos() + 1.).trunc()
+        }
+        EdgeWeightType::Explicit => panic!("EXPLICIT instances have no distance function"),
+    }
+}
+
+/// Arredondamento para o inteiro mais próximo, como o `nint` da TSPLIB.
+fn nint(x: f64) -> f64 {
+    (x + 0.5).trunc()
+}
+
+/// Converte uma coordenada no formato `GRAUS.MINUTOS` da TSPLIB para radianos.
+fn geo_radians(x: f64) -> f64 {
+    // A especificação usa esta aproximação de π, e não `std::f64::consts::PI`.
+    #[allow(clippy::approx_constant)]
+    const PI: f64 = 3.141592;
+    let deg = x.trunc();
+    let min = x - deg;
+    PI * (deg + 5. * min / 3.) / 180.
+}
+
+/// Monta a matriz completa a partir dos valores de `EDGE_WEIGHT_SECTION`.
+fn explicit_matrix(n: usize, format: EdgeWeightFormat, w: &[f64]) -> Matrix {
+    let mut m = Matrix::filled(n, 0.);
+    let mut values = w.iter().copied();
+
+    for i in 0..n {
+        let columns = match format {
+            EdgeWeightFormat::FullMatrix => 0..n,
+            EdgeWeightFormat::UpperRow => i + 1..n,
+            EdgeWeightFormat::UpperDiagRow => i..n,
+            EdgeWeightFormat::LowerRow => 0..i,
+            EdgeWeightFormat::LowerDiagRow => 0..i + 1,
+        };
+        for j in columns {
+            // A quantidade de valores já foi conferida durante a leitura da seção.
+            let d = values.next().unwrap_or_default();
+            m[i][j] = d;
+            if format != EdgeWeightFormat::FullMatrix {
+                m[j][i] = d;
+            }
+        }
+    }
+
+    for i in 0..n {
+        m[i][i] = 0.;
+    }
+    m
+}
+
+fn numbers<'a>(tokens: impl Iterator<Item = &'a str>, section: &str) -> io::Result<Vec<f64>> {
+    tokens
+        .map(|t| {
+            t.parse()
+                .map_err(|_| invalid_data(format!("{section}: `{t}` is not a number")))
+        })
+        .collect()
+}
+
+fn section_before_dimension(section: &str) -> io::Error {
+    invalid_data(format!("{section} appears before DIMENSION"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::DistanceMatrix;
+
+    #[test]
+    fn euc_2d_test() {
+        let tsp = parse(
+            "NAME: square\nTYPE: TSP\nDIMENSION: 4\nEDGE_WEIGHT_TYPE: EUC_2D\n\
+             NODE_COORD_SECTION\n1 0 0\n2 3 0\n3 3 4\n4 0 4\nEOF\n",
+        )
+        .unwrap();
+
+        assert_eq!(tsp.name, "square");
+        assert_eq!(tsp.matrix.node_count(), 4);
+        assert_eq!(tsp.matrix[0][2], 5.0);
+        assert_eq!(tsp.matrix[1][2], 4.0);
+        assert_eq!(tsp.coords.unwrap()[2], (3.0, 4.0));
+    }
+
+    #[test]
+    fn explicit_formats_test() {
+        let header = "NAME: t\nTYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\n";
+        let full = parse(&format!(
+            "{header}EDGE_WEIGHT_FORMAT: FULL_MATRIX\nEDGE_WEIGHT_SECTION\n0 1 2\n1 0 3\n2 3 0\nEOF"
+        ))
+        .unwrap();
+        let upper = parse(&format!(
+            "{header}EDGE_WEIGHT_FORMAT: UPPER_ROW\nEDGE_WEIGHT_SECTION\n1 2\n3\nEOF"
+        ))
+        .unwrap();
+        let lower = parse(&format!(
+            "{header}EDGE_WEIGHT_FORMAT: LOWER_DIAG_ROW\nEDGE_WEIGHT_SECTION\n0 1 0 2 3 0\nEOF"
+        ))
+        .unwrap();
+
+        assert_eq!(full.matrix, upper.matrix);
+        assert_eq!(full.matrix, lower.matrix);
+        assert!(full.coords.is_none());
+    }
+
+    #[test]
+    fn extra_weights_test() {
+        let error = parse(
+            "NAME: t\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: UPPER_ROW\n\
+             EDGE_WEIGHT_SECTION\n1 2\n3 4\nEOF",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "EDGE_WEIGHT_SECTION: expected 3 values, found 4"
+        );
+    }
+
+    #[test]
+    fn att_and_geo_test() {
+        // Primeiras cidades da att48 e da ulysses16.
+        assert_eq!(
+            distance(EdgeWeightType::Att, (6734., 1453.), (2233., 10.)),
+            1495.
+        );
+        assert_eq!(
+            distance(EdgeWeightType::Geo, (38.24, 20.42), (39.57, 26.15)),
+            509.
+        );
+    }
+
+    #[test]
+    fn missing_section_test() {
+        assert!(parse("NAME: t\nDIMENSION: 2\nEDGE_WEIGHT_TYPE: EUC_2D\nEOF").is_err());
+        assert!(parse("NAME: t\nEDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 0 0\n").is_err());
+    }
+}
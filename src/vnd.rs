@@ -0,0 +1,191 @@
+//! Descida em vizinhança variável (VND).
+//!
+//! A VND percorre uma lista ordenada de vizinhanças: enquanto a vizinhança atual melhora a
+//! solução, a busca volta para a primeira vizinhança da lista; quando ela não melhora, a busca
+//! passa para a próxima. A VND termina quando nenhuma das vizinhanças melhora a solução, ou seja,
+//! em um ótimo local de todas elas.
+
+use crate::graphs::DistanceMatrix;
+use crate::local_search::{Neighbourhood, Policy, Solution};
+
+/// Melhora mínima para que uma vizinhança seja considerada de melhora.
+const EPSILON: f64 = 1e-9;
+
+/// Configuração da VND.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vnd {
+    /// Vizinhanças, na ordem em que são exploradas.
+    pub neighbourhoods: Vec<Neighbourhood>,
+    /// Política de cada passo. Por padrão, cada passo aplica o melhor movimento da vizinhança.
+    pub policy: Policy,
+}
+
+/// Melhora encontrada por uma vizinhança durante a VND.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Improvement {
+    pub neighbourhood: Neighbourhood,
+    /// Quantidade de movimentos aplicados no passo.
+    pub moves: usize,
+    /// Custo da solução após o passo.
+    pub cost: f64,
+}
+
+/// Resultado da VND: a solução final e as melhoras, em ordem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VndResult<S> {
+    pub solution: S,
+    pub improvements: Vec<Improvement>,
+}
+
+impl<S> VndResult<S> {
+    /// Quantidade de melhoras encontradas por `neighbourhood`.
+    pub fn count(&self, neighbourhood: Neighbourhood) -> usize {
+        self.improvements
+            .iter()
+            .filter(|i| i.neighbourhood == neighbourhood)
+            .count()
+    }
+}
+
+impl Vnd {
+    /// VND sobre `neighbourhoods`, aplicando um movimento (o melhor) por passo.
+    pub fn new(neighbourhoods: Vec<Neighbourhood>) -> Self {
+        Self {
+            neighbourhoods,
+            policy: Policy::best_improvement().with_max_moves(1),
+        }
+    }
+
+    /// Troca a política de cada passo (e.g. para descer até um ótimo local em cada vizinhança).
+    pub fn with_policy(self, policy: Policy) -> Self {
+        Self { policy, ..self }
+    }
+
+    /// Executa a VND a partir de `solution`.
+    pub fn run<const N: usize, G: DistanceMatrix>(
+        &self,
+        solution: &Solution<N>,
+        graph: &G,
+    ) -> VndResult<Solution<N>> {
+        let mut solution = solution.clone();
+        let mut improvements = Vec::new();
+        let mut k = 0;
+
+        while let Some(&neighbourhood) = self.neighbourhoods.get(k) {
+            match self.step(neighbourhood, &solution, graph) {
+                Some((next, moves)) => {
+                    improvements.push(Improvement {
+                        neighbourhood,
+                        moves,
+                        cost: next.cost,
+                    });
+                    solution = next;
+                    k = 0;
+                }
+                None => k += 1,
+            }
+        }
+
+        VndResult {
+            solution,
+            improvements,
+        }
+    }
+
+    /// Aplica `neighbourhood` e retorna a nova solução, caso ela seja melhor. As vizinhanças swap
+    /// e shift, que movem apenas o vértice de uma posição, são tentadas a partir de cada posição
+    /// até que uma delas melhore a solução.
+    fn step<const N: usize, G: DistanceMatrix>(
+        &self,
+        neighbourhood: Neighbourhood,
+        solution: &Solution<N>,
+        graph: &G,
+    ) -> Option<(Solution<N>, usize)> {
+        let starts = match neighbourhood {
+            Neighbourhood::Swap | Neighbourhood::Shift => 0..solution.route.len(),
+            _ => 0..1,
+        };
+        starts
+            .map(|start| neighbourhood.search_with(solution, graph, start, self.policy))
+            .find(|d| d.solution.cost < solution.cost - EPSILON)
+            .map(|d| (d.solution, d.moves))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::local_search::{DynSolution, LocalSearch};
+    const INF: f64 = f64::INFINITY;
+
+    fn graph() -> Vec<Vec<f64>> {
+        vec![
+            vec![INF, 1.0, 2.0, 4.0, 3.0, 9.0],
+            vec![1.0, INF, 7.0, 2.0, 5.0, 6.0],
+            vec![2.0, 7.0, INF, 8.0, 1.0, 3.0],
+            vec![4.0, 2.0, 8.0, INF, 6.0, 2.0],
+            vec![3.0, 5.0, 1.0, 6.0, INF, 4.0],
+            vec![9.0, 6.0, 3.0, 2.0, 4.0, INF],
+        ]
+    }
+
+    #[test]
+    fn vnd_test_1() {
+        let graph = graph();
+        let solution = DynSolution {
+            route: vec![0, 5, 1, 4, 3, 2],
+            cost: 0.0,
+        };
+        let solution = DynSolution {
+            cost: DynSolution::calculate_cost(&solution.route, &graph),
+            ..solution
+        };
+
+        let vnd = Vnd::new(vec![Neighbourhood::Swap, Neighbourhood::TwoOpt]);
+        let result = vnd.run(&solution, &graph);
+
+        assert!(!result.improvements.is_empty());
+        assert!(result.solution.cost < solution.cost);
+        assert_eq!(
+            result.solution.cost,
+            result.improvements.last().unwrap().cost
+        );
+        assert_eq!(
+            result.count(Neighbourhood::Swap) + result.count(Neighbourhood::TwoOpt),
+            result.improvements.len()
+        );
+        assert!(
+            result
+                .improvements
+                .windows(2)
+                .all(|w| w[1].cost < w[0].cost)
+        );
+
+        // O resultado é um ótimo local de todas as vizinhanças.
+        for start in 0..6 {
+            assert_eq!(
+                result.solution.swap(&graph, start).cost,
+                result.solution.cost
+            );
+        }
+        assert_eq!(result.solution.two_opt(&graph).cost, result.solution.cost);
+    }
+
+    #[test]
+    fn vnd_test_2() {
+        let graph = graph();
+        let solution = DynSolution {
+            route: vec![0, 1, 3, 5, 2, 4],
+            cost: 0.0,
+        };
+        let solution = DynSolution {
+            cost: DynSolution::calculate_cost(&solution.route, &graph),
+            ..solution
+        };
+
+        // A solução já é ótima: nenhuma vizinhança melhora.
+        let result = Vnd::new(Neighbourhood::ALL.to_vec()).run(&solution, &graph);
+        assert_eq!(result.solution.route, solution.route);
+        assert!(result.improvements.is_empty());
+    }
+}